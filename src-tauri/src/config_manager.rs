@@ -5,6 +5,199 @@ use anyhow::{bail, Result};
 use serde::{Serialize, Deserialize};
 use directories::ProjectDirs;
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IdentityProfile {
+    profile_name: String,
+    name: String,
+    email: String,
+}
+
+impl IdentityProfile {
+    pub fn new(profile_name: String, name: String, email: String) -> Self {
+        Self {
+            profile_name,
+            name,
+            email,
+        }
+    }
+
+    pub fn borrow_profile_name(&self) -> &String {
+        &self.profile_name
+    }
+
+    pub fn borrow_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn borrow_email(&self) -> &String {
+        &self.email
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CredentialProfile {
+    profile_name: String,
+    // Either a host (like github.com) or the start of a remote URL (like https://github.com/my-org/).
+    url_pattern: String,
+    cred_type: String,
+    https_username: Option<String>,
+    public_key_path: Option<PathBuf>,
+    private_key_path: Option<PathBuf>,
+    uses_passphrase: Option<bool>,
+}
+
+impl CredentialProfile {
+    pub fn new(profile_name: String, url_pattern: String, cred_type: String) -> Self {
+        Self {
+            profile_name,
+            url_pattern,
+            cred_type,
+            https_username: None,
+            public_key_path: None,
+            private_key_path: None,
+            uses_passphrase: None,
+        }
+    }
+
+    pub fn borrow_profile_name(&self) -> &String {
+        &self.profile_name
+    }
+
+    pub fn borrow_url_pattern(&self) -> &String {
+        &self.url_pattern
+    }
+
+    pub fn borrow_cred_type(&self) -> &String {
+        &self.cred_type
+    }
+
+    pub fn borrow_https_username(&self) -> &Option<String> {
+        &self.https_username
+    }
+
+    pub fn borrow_public_key_path(&self) -> &Option<PathBuf> {
+        &self.public_key_path
+    }
+
+    pub fn borrow_private_key_path(&self) -> &Option<PathBuf> {
+        &self.private_key_path
+    }
+
+    pub fn borrow_uses_passphrase(&self) -> &Option<bool> {
+        &self.uses_passphrase
+    }
+
+    pub fn set_https_username(&mut self, new_username: String) {
+        self.https_username = Some(new_username);
+    }
+
+    pub fn set_public_key_path(&mut self, public_key_path: PathBuf) {
+        self.public_key_path = Some(public_key_path);
+    }
+
+    pub fn set_private_key_path(&mut self, private_key_path: PathBuf) {
+        self.private_key_path = Some(private_key_path);
+    }
+
+    pub fn set_uses_passphrase(&mut self, uses_passphrase: bool) {
+        self.uses_passphrase = Some(uses_passphrase);
+    }
+
+    // The keychain account holding this profile's password or passphrase. The credentials set from the
+    // menu bar have no profile name and keep using the original accounts.
+    pub fn get_keychain_account(&self, secret_kind: &str) -> String {
+        if self.profile_name == "" {
+            String::from(secret_kind)
+        } else {
+            format!("{}:{}", secret_kind, self.profile_name)
+        }
+    }
+
+    // How specific the match is, so a pattern with more of the path (then a port, then a scheme) wins.
+    fn get_match_specificity(&self, url: &str) -> Option<(usize, bool, bool)> {
+        let pattern = parse_url_pattern(&self.url_pattern)?;
+        let remote = parse_remote_url(url)?;
+        if let Some(scheme) = &pattern.scheme {
+            if remote.scheme.as_ref().map_or(true, |s| !s.eq_ignore_ascii_case(scheme)) {
+                return None;
+            }
+        }
+        if !pattern.host.eq_ignore_ascii_case(&remote.host) {
+            return None;
+        }
+        if pattern.port.is_some() && pattern.port != remote.port {
+            return None;
+        }
+        // Only whole path segments match, so host/org doesn't match host/organization-other.
+        if pattern.path_segments.len() > remote.path_segments.len() {
+            return None;
+        }
+        if pattern.path_segments.iter().zip(remote.path_segments.iter()).any(|(p, r)| p != r) {
+            return None;
+        }
+        Some((pattern.path_segments.len(), pattern.port.is_some(), pattern.scheme.is_some()))
+    }
+}
+
+struct ParsedUrl {
+    scheme: Option<String>,
+    host: String,
+    port: Option<String>,
+    path_segments: Vec<String>,
+}
+
+// Handles both URLs (https://user@host:port/path) and scp-like SSH remotes (user@host:path).
+fn parse_remote_url(url: &str) -> Option<ParsedUrl> {
+    if let Some(i) = url.find("://") {
+        return parse_url_parts(Some(&url[..i]), &url[(i + 3)..], false);
+    }
+    parse_url_parts(None, url, true)
+}
+
+// Patterns may also be a bare host, optionally followed by a path (github.com/org).
+fn parse_url_pattern(pattern: &str) -> Option<ParsedUrl> {
+    if let Some(i) = pattern.find("://") {
+        return parse_url_parts(Some(&pattern[..i]), &pattern[(i + 3)..], false);
+    }
+    let authority_end = pattern.find('/').unwrap_or(pattern.len());
+    let is_scp_like = pattern[..authority_end].contains(':');
+    parse_url_parts(None, pattern, is_scp_like)
+}
+
+fn parse_url_parts(scheme: Option<&str>, after_scheme: &str, is_scp_like: bool) -> Option<ParsedUrl> {
+    let (authority, path) = if is_scp_like {
+        let authority_end = after_scheme.find(':')?;
+        (&after_scheme[..authority_end], &after_scheme[(authority_end + 1)..])
+    } else {
+        match after_scheme.find('/') {
+            Some(i) => (&after_scheme[..i], &after_scheme[i..]),
+            None => (after_scheme, ""),
+        }
+    };
+    let host_and_port = match authority.rfind('@') {
+        Some(i) => &authority[(i + 1)..],
+        None => authority,
+    };
+    let (host, port) = match host_and_port.rfind(':') {
+        Some(i) if !is_scp_like => (&host_and_port[..i], Some(String::from(&host_and_port[(i + 1)..]))),
+        _ => (host_and_port, None),
+    };
+    if host == "" {
+        return None;
+    }
+    let path_segments = path.split('/')
+        .filter(|segment| *segment != "")
+        .map(String::from)
+        .collect();
+    Some(ParsedUrl {
+        scheme: scheme.map(String::from),
+        host: String::from(host),
+        port,
+        path_segments,
+    })
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -15,6 +208,14 @@ pub struct Config {
     public_key_path: Option<PathBuf>,
     private_key_path: Option<PathBuf>,
     uses_passphrase: Option<bool>,
+    credential_profiles: Option<Vec<CredentialProfile>>,
+    identity_profiles: Option<Vec<IdentityProfile>>,
+    default_identity_profile: Option<String>,
+    sign_commits: Option<bool>,
+    sign_tags: Option<bool>,
+    signing_format: Option<String>,
+    signing_key: Option<String>,
+    pull_strategy: Option<String>,
 }
 
 impl Config {
@@ -27,6 +228,14 @@ impl Config {
             public_key_path: None,
             private_key_path: None,
             uses_passphrase: None,
+            credential_profiles: None,
+            identity_profiles: None,
+            default_identity_profile: None,
+            sign_commits: None,
+            sign_tags: None,
+            signing_format: None,
+            signing_key: None,
+            pull_strategy: None,
         }
     }
 
@@ -58,6 +267,75 @@ impl Config {
         &self.uses_passphrase
     }
 
+    pub fn borrow_credential_profiles(&self) -> &Option<Vec<CredentialProfile>> {
+        &self.credential_profiles
+    }
+
+    pub fn borrow_identity_profiles(&self) -> &Option<Vec<IdentityProfile>> {
+        &self.identity_profiles
+    }
+
+    pub fn borrow_default_identity_profile(&self) -> &Option<String> {
+        &self.default_identity_profile
+    }
+
+    pub fn borrow_sign_commits(&self) -> &Option<bool> {
+        &self.sign_commits
+    }
+
+    pub fn borrow_sign_tags(&self) -> &Option<bool> {
+        &self.sign_tags
+    }
+
+    pub fn borrow_signing_format(&self) -> &Option<String> {
+        &self.signing_format
+    }
+
+    pub fn borrow_signing_key(&self) -> &Option<String> {
+        &self.signing_key
+    }
+
+    pub fn borrow_pull_strategy(&self) -> &Option<String> {
+        &self.pull_strategy
+    }
+
+    pub fn get_identity_profile(&self, profile_name: &str) -> Option<&IdentityProfile> {
+        match &self.identity_profiles {
+            Some(profiles) => profiles.iter().find(|p| p.profile_name == profile_name),
+            None => None,
+        }
+    }
+
+    pub fn get_credential_profile(&self, profile_name: &str) -> Option<&CredentialProfile> {
+        match &self.credential_profiles {
+            Some(profiles) => profiles.iter().find(|p| p.profile_name == profile_name),
+            None => None,
+        }
+    }
+
+    // The profile with the most specific pattern matching the URL wins, otherwise the credentials set from
+    // the menu bar are used if there are any.
+    pub fn get_credential_profile_for_url(&self, url: &str) -> Option<CredentialProfile> {
+        if let Some(profiles) = &self.credential_profiles {
+            let best_match_opt = profiles.iter()
+                .filter_map(|p| p.get_match_specificity(url).map(|specificity| (specificity, p)))
+                .max_by_key(|(specificity, _)| *specificity);
+            if let Some((_, profile)) = best_match_opt {
+                return Some(profile.clone());
+            }
+        }
+        let cred_type = self.cred_type.clone()?;
+        Some(CredentialProfile {
+            profile_name: String::new(),
+            url_pattern: String::new(),
+            cred_type,
+            https_username: self.https_username.clone(),
+            public_key_path: self.public_key_path.clone(),
+            private_key_path: self.private_key_path.clone(),
+            uses_passphrase: self.uses_passphrase,
+        })
+    }
+
     pub fn set_cred_type(&mut self, cred_type: String) {
         self.cred_type = Some(cred_type);
     }
@@ -78,6 +356,41 @@ impl Config {
         self.uses_passphrase = Some(uses_passphrase);
     }
 
+    pub fn set_credential_profile(&mut self, credential_profile: CredentialProfile) {
+        let profiles = self.credential_profiles.get_or_insert(vec![]);
+        match profiles.iter().position(|p| p.profile_name == credential_profile.profile_name) {
+            Some(i) => profiles[i] = credential_profile,
+            None => profiles.push(credential_profile),
+        };
+    }
+
+    pub fn remove_credential_profile(&mut self, profile_name: &str) {
+        if let Some(profiles) = &mut self.credential_profiles {
+            profiles.retain(|p| p.profile_name != profile_name);
+        }
+    }
+
+    pub fn set_identity_profile(&mut self, identity_profile: IdentityProfile) {
+        let profiles = self.identity_profiles.get_or_insert(vec![]);
+        match profiles.iter().position(|p| p.profile_name == identity_profile.profile_name) {
+            Some(i) => profiles[i] = identity_profile,
+            None => profiles.push(identity_profile),
+        };
+    }
+
+    pub fn remove_identity_profile(&mut self, profile_name: &str) {
+        if let Some(profiles) = &mut self.identity_profiles {
+            profiles.retain(|p| p.profile_name != profile_name);
+        }
+        if self.default_identity_profile.as_deref() == Some(profile_name) {
+            self.default_identity_profile = None;
+        }
+    }
+
+    pub fn set_default_identity_profile(&mut self, profile_name_opt: Option<String>) {
+        self.default_identity_profile = profile_name_opt;
+    }
+
     pub fn save(&self) -> Result<()> {
         let config_path_buf = get_config_path()?;
         let config_path = config_path_buf.as_path();
@@ -113,7 +426,18 @@ fn save_default_config() -> Result<()> {
 }
 
 pub fn save_config_from_json(payload: &str) -> Result<()> {
-    let config: Config = serde_json::from_str(payload)?;
+    // The front-end only sends the preferences it displays, and leaves out the ones that were cleared, so those
+    // are replaced as a whole. Credentials and profiles are managed elsewhere and are kept from the saved config.
+    let mut config: Config = serde_json::from_str(payload)?;
+    let saved_config = get_config()?;
+    config.cred_type = saved_config.cred_type;
+    config.https_username = saved_config.https_username;
+    config.public_key_path = saved_config.public_key_path;
+    config.private_key_path = saved_config.private_key_path;
+    config.uses_passphrase = saved_config.uses_passphrase;
+    config.credential_profiles = saved_config.credential_profiles;
+    config.identity_profiles = saved_config.identity_profiles;
+    config.default_identity_profile = saved_config.default_identity_profile;
     config.save()?;
     Ok(())
 }
@@ -1,15 +1,32 @@
+use std::cell::RefCell;
+use std::os::raw::c_int;
 use std::collections::{HashMap, VecDeque};
 use std::fs::create_dir_all;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::{fs, str};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::{Arc, LockResult, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use std::{fmt, fs, str};
 use anyhow::{bail, Result};
-use git2::{AutotagOption, Branch, BranchType, Commit, Cred, Delta, Diff, DiffFindOptions, DiffLine, DiffLineType, DiffOptions, ErrorCode, FetchOptions, FetchPrune, IndexAddOption, ObjectType, Oid, Patch, PushOptions, Rebase, Reference, RemoteCallbacks, Repository, ResetType, Signature, Sort, StashFlags};
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use directories::BaseDirs;
+use git2::{ApplyLocation, AutotagOption, BlameOptions, Branch, BranchType, CertificateCheckStatus, Commit, Config, ConfigLevel, Cred, CredentialHelper, CredentialType, Delta, Diff, DiffFindOptions, DiffLine, DiffLineType, DiffOptions, Direction, ErrorClass, ErrorCode, FetchOptions, FetchPrune, Index, IndexAddOption, IndexConflict, IndexEntry, IndexTime, Object, ObjectType, Oid, PackBuilderStage, Patch, PushOptions, Rebase, Reference, Remote, RemoteCallbacks, Repository, RepositoryState, ResetType, Signature, Sort, StashApplyOptions, StashFlags, StatusOptions, Submodule, SubmoduleIgnore, SubmoduleStatus, SubmoduleUpdateOptions, Tree, Worktree, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
 use git2::build::{CheckoutBuilder, RepoBuilder};
-use serde::{Serialize, Serializer};
+use git2::cert::{Cert, SshHostKeyType};
+use git2::string_array::StringArray;
+use git2::message_prettify;
+use regex::Regex;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 use time::UtcOffset;
 use crate::parseable_info::{get_parseable_diff_delta, ParseableDiffDelta};
+use crate::svg_row::GraphLaneState;
 use crate::config_manager;
+use crate::config_manager::{CredentialProfile, IdentityProfile};
 
 fn trim_newline(s: &mut String) {
     if s.ends_with('\n') {
@@ -80,1407 +97,5378 @@ impl FileInfo {
     }
 }
 
+#[derive(Clone, Deserialize)]
+pub struct SelectedLineInfo {
+    old_lineno: Option<u32>,
+    new_lineno: Option<u32>,
+    origin: char,
+}
+
+impl SelectedLineInfo {
+    fn matches_diff_line(&self, diff_line: &DiffLine) -> bool {
+        self.origin == diff_line.origin() && self.old_lineno == diff_line.old_lineno() && self.new_lineno == diff_line.new_lineno()
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct PartialChangeInfo {
+    file_path: String,
+    #[serde(default)]
+    hunk_indexes: Vec<usize>,
+    #[serde(default)]
+    lines: Vec<SelectedLineInfo>,
+}
+
+impl PartialChangeInfo {
+    fn is_line_selected(&self, hunk_index: usize, diff_line: &DiffLine) -> bool {
+        self.hunk_indexes.contains(&hunk_index) || self.lines.iter().any(|l| l.matches_diff_line(diff_line))
+    }
+}
+
 #[derive(Clone, Serialize)]
-pub struct CommitInfo {
-    sha: String,
+pub struct AmendInfo {
     summary: String,
     message: String,
-    author_name: String,
-    author_time: i64,
-    committer_name: String,
-    committer_time: i64,
-    changed_files: Vec<ParseableDiffDelta>,
+    is_merge_commit: bool,
+    is_pushed: bool,
+    upstream_name: Option<String>,
 }
 
-impl CommitInfo {
-    pub fn from_commit(commit: Commit, repo: &Repository) -> Result<Self> {
-        let author_signature = commit.author();
-        let author_name = String::from(GitManager::get_utf8_string(author_signature.name(), "Author Name")?);
-        let author_time = author_signature.when().seconds();
+// The bits of an index entry's flags that hold its conflict stage (matches libgit2's GIT_INDEX_ENTRY_STAGEMASK).
+const INDEX_ENTRY_STAGE_MASK: u16 = 0x3000;
 
-        let committer_signature = commit.committer();
-        let committer_name = String::from(GitManager::get_utf8_string(committer_signature.name(), "Committer Name")?);
-        let committer_time = committer_signature.when().seconds();
+#[derive(Clone, Serialize)]
+pub struct ConflictInfo {
+    path: String,
+    ancestor_sha: Option<String>,
+    our_sha: Option<String>,
+    their_sha: Option<String>,
+}
 
-        let diff = get_commit_changes(&commit, repo)?;
-        let parseable_diff_delta = get_parseable_diff_delta(diff)?;
+#[derive(Clone, Serialize)]
+pub struct ConflictsInfo {
+    operation: Option<String>,
+    conflicts: Vec<ConflictInfo>,
+}
 
-        let new_commit_info = Self {
-            sha: commit.id().to_string(),
-            summary: html_escape::encode_text(&String::from(GitManager::get_utf8_string(commit.summary(), "Commit Summary")?)).parse()?,
-            message: html_escape::encode_text(&String::from(GitManager::get_utf8_string(commit.message(), "Commit Message")?)).parse()?,
-            author_name: html_escape::encode_text(&author_name).parse()?,
-            author_time,
-            committer_name: html_escape::encode_text(&committer_name).parse()?,
-            committer_time,
-            changed_files: parseable_diff_delta,
-        };
+#[derive(Clone, Serialize)]
+pub struct ConflictVersions {
+    path: String,
+    is_binary: bool,
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+    merged: Option<String>,
+}
 
-        Ok(new_commit_info)
+const PULL_STRATEGIES: [&str; 3] = ["merge", "rebase", "ff-only"];
+
+const REBASE_ACTIONS: [&str; 6] = ["pick", "reword", "edit", "squash", "fixup", "drop"];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RebaseTodoItem {
+    action: String,
+    sha: String,
+    #[serde(default)]
+    summary: String,
+    // Only used by "reword" and "squash" to replace the commit message.
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct StashOptions {
+    #[serde(default)]
+    message: String,
+    // When empty, everything is stashed.
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    keep_index: bool,
+    #[serde(default = "StashOptions::default_include_untracked")]
+    include_untracked: bool,
+}
+
+impl StashOptions {
+    fn default_include_untracked() -> bool {
+        true
+    }
+
+    fn get_stash_flags(&self) -> StashFlags {
+        let mut stash_flags = StashFlags::DEFAULT;
+        if self.keep_index {
+            stash_flags |= StashFlags::KEEP_INDEX;
+        }
+        if self.include_untracked {
+            stash_flags |= StashFlags::INCLUDE_UNTRACKED;
+        }
+        stash_flags
     }
 }
 
-fn get_commit_changes<'a, 'b>(commit: &'a Commit, repo: &'b Repository) -> Result<Diff<'b>> {
-    let commit_tree = commit.tree()?;
+#[derive(Clone, Deserialize)]
+pub struct InteractiveRebaseInfo {
+    onto_sha: String,
+    todo_list: Vec<RebaseTodoItem>,
+}
 
-    let mut diff_opt = None;
-    for parent_commit in commit.parents() {
-        let mut diff = repo.diff_tree_to_tree(Some(&parent_commit.tree()?), Some(&commit_tree), None)?;
-        GitManager::set_diff_find_similar(&mut diff)?;
-        // For merge commits, the diff between a merge commit and the parent from the branch that was merged will be empty,
-        // so find the diff that's populated.
-        if diff.stats()?.files_changed() > 0 {
-            return Ok(diff);
+#[derive(Clone, Serialize, Deserialize)]
+struct InteractiveRebaseState {
+    head_name: Option<String>,
+    orig_head_sha: String,
+    onto_sha: String,
+    todo_list: Vec<RebaseTodoItem>,
+    next_step: usize,
+    // Set when the step at next_step has been cherry-picked but stopped on conflicts before committing.
+    is_step_pending: bool,
+    // The last commit this rebase put on HEAD, which is the only commit "squash" and "fixup" may amend.
+    #[serde(default)]
+    squash_target_sha: Option<String>,
+}
+
+impl InteractiveRebaseState {
+    // git doesn't know about this rebase, so it's kept in the app's own file rather than in a partial rebase-merge
+    // directory that git and libgit2 would try to resume.
+    fn get_path(repo: &Repository) -> PathBuf {
+        repo.path().join("oxidized_git_rebase.json")
+    }
+
+    fn load(repo: &Repository) -> Result<Option<Self>> {
+        let state_path = InteractiveRebaseState::get_path(repo);
+        if !state_path.exists() {
+            return Ok(None);
         }
-        diff_opt = Some(diff);
+        let state: Self = serde_json::from_str(&*fs::read_to_string(state_path)?)?;
+        Ok(Some(state))
     }
 
-    // If there are parents but no diff was returned earlier, then the commit is empty and should return the empty diff.
-    if let Some(d) = diff_opt {
-        return Ok(d);
+    fn save(&self, repo: &Repository) -> Result<()> {
+        fs::write(InteractiveRebaseState::get_path(repo), serde_json::to_string_pretty(&self)?)?;
+        Ok(())
     }
 
-    // If there are no parents, get the diff between this commit and nothing.
-    let mut diff = repo.diff_tree_to_tree(None, Some(&commit_tree), None)?;
-    GitManager::set_diff_find_similar(&mut diff)?;
+    fn delete(repo: &Repository) -> Result<()> {
+        let state_path = InteractiveRebaseState::get_path(repo);
+        if state_path.exists() {
+            fs::remove_file(state_path)?;
+        }
+        Ok(())
+    }
 
-    Ok(diff)
+    // Cherry-picking a step leaves cherry-pick state behind, but repo.cleanup_state() would also remove MERGE_MSG
+    // while a step is still waiting on conflicts.
+    fn remove_cherrypick_state(repo: &Repository, is_step_committed: bool) -> Result<()> {
+        let mut file_names = vec!["CHERRY_PICK_HEAD"];
+        if is_step_committed {
+            file_names.push("MERGE_MSG");
+        }
+        for file_name in file_names {
+            let file_path = repo.path().join(file_name);
+            if file_path.exists() {
+                fs::remove_file(file_path)?;
+            }
+        }
+        Ok(())
+    }
 }
 
-pub struct GitManager {
-    repo: Option<Repository>,
-    old_graph_starting_shas: Vec<String>,
-    current_local_offset: UtcOffset,
+// Which refs the commit graph starts from. This is saved in the repo's common git directory so each repo keeps its own,
+// shared by all of its worktrees.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GraphScope {
+    // One of "all", "local", "current", or "selected".
+    scope: String,
+    // Full reference names (ex. refs/heads/main or refs/remotes/origin/main) used by the "selected" scope.
+    #[serde(default)]
+    selected_branches: Vec<String>,
+    #[serde(default)]
+    include_tags: bool,
+    #[serde(default)]
+    include_stashes: bool,
 }
 
-impl GitManager {
-    pub fn new(current_local_offset: UtcOffset) -> Self {
+impl GraphScope {
+    fn new_default() -> Self {
         Self {
-            repo: None,
-            old_graph_starting_shas: vec![],
-            current_local_offset,
+            scope: String::from("all"),
+            selected_branches: vec![],
+            include_tags: false,
+            include_stashes: false,
         }
     }
 
-    pub fn get_utf8_string<'a, 'b>(value: Option<&'a str>, str_name_type: &'b str) -> Result<&'a str> {
-        match value {
-            Some(n) => Ok(n),
-            None => bail!(format!("{} uses invalid utf-8!", str_name_type)),
+    // git2 0.18 doesn't expose the common directory, so a linked worktree's is found the same way git finds it.
+    fn get_path(repo: &Repository) -> Result<PathBuf> {
+        let mut scope_path = repo.path().to_path_buf();
+        let commondir_path = repo.path().join("commondir");
+        if repo.is_worktree() && commondir_path.exists() {
+            // The path is usually relative to the worktree's git directory, but joining keeps an absolute one as is.
+            scope_path.push(fs::read_to_string(commondir_path)?.trim());
         }
+        scope_path.push("oxidized_git_graph_scope.json");
+        Ok(scope_path)
     }
 
-    fn get_string_from_serde_string(value: Option<&str>) -> Result<&str> {
-        match value {
-            Some(s) => Ok(s),
-            None => bail!("Invalid JSON String!"),
+    fn load(repo: &Repository) -> Result<Self> {
+        let scope_path = GraphScope::get_path(repo)?;
+        if !scope_path.exists() {
+            return Ok(GraphScope::new_default());
         }
+        let graph_scope: Self = serde_json::from_str(&*fs::read_to_string(scope_path)?)?;
+        Ok(graph_scope)
     }
 
-    pub fn borrow_current_local_offset(&self) -> &UtcOffset {
-        &self.current_local_offset
+    fn save(&self, repo: &Repository) -> Result<()> {
+        fs::write(GraphScope::get_path(repo)?, serde_json::to_string_pretty(&self)?)?;
+        Ok(())
     }
+}
 
-    pub fn borrow_repo(&self) -> Result<&Repository> {
-        let repo_temp_opt = &self.repo;
-        match repo_temp_opt {
-            Some(repo) => Ok(repo),
-            None => bail!("No repo loaded to perform operation on."),
+#[derive(Clone, Serialize)]
+pub struct RemoteInfo {
+    name: String,
+    url: Option<String>,
+    // Only set when pushes go to a different URL than fetches.
+    push_url: Option<String>,
+    fetch_refspecs: Vec<String>,
+    push_refspecs: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct RemoteFetchRefspecs {
+    remote_name: String,
+    fetch_refspecs: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct RemoteProgress {
+    // "clone", "fetch" or "push".
+    operation: String,
+    // "receiving", "resolving", "counting", "compressing", "uploading" or "remote" for messages from the server.
+    phase: String,
+    remote_name: String,
+    current_objects: usize,
+    total_objects: usize,
+    bytes: usize,
+    message: Option<String>,
+}
+
+impl RemoteProgress {
+    fn new(operation: &str, phase: &str, remote_name: &str, current_objects: usize, total_objects: usize, bytes: usize, message: Option<String>) -> Self {
+        Self {
+            operation: String::from(operation),
+            phase: String::from(phase),
+            remote_name: String::from(remote_name),
+            current_objects,
+            total_objects,
+            bytes,
+            message,
         }
     }
+}
 
-    pub fn borrow_repo_mut(&mut self) -> Result<&mut Repository> {
-        let repo_temp_opt = &mut self.repo;
-        match repo_temp_opt {
-            Some(repo) => Ok(repo),
-            None => bail!("No repo loaded to perform operation on."),
+// Returned when the user cancels an operation, so it can be told apart from an actual failure.
+#[derive(Debug)]
+pub struct OperationCancelled;
+
+impl fmt::Display for OperationCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The operation was cancelled.")
+    }
+}
+
+impl std::error::Error for OperationCancelled {}
+
+// Shared outside of the GitManager's mutex so an operation can be cancelled while it holds the lock.
+// Every operation gets an id before it waits for the lock, and a cancel stops every operation with an id handed out
+// before it, so it applies to the running operation and any waiting behind it but never to a later one.
+// Note that cancelling is only noticed in libgit2's callbacks, so a fetch or push stuck connecting to the server
+// can't be cancelled until the connection attempt hits SERVER_CONNECT_TIMEOUT.
+#[derive(Clone)]
+pub struct CancelHandle {
+    last_operation_id: Arc<AtomicU64>,
+    cancelled_through_id: Arc<AtomicU64>,
+    current_operation_id: Arc<AtomicU64>,
+    has_step_started: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    fn new() -> Self {
+        Self {
+            last_operation_id: Arc::new(AtomicU64::new(0)),
+            cancelled_through_id: Arc::new(AtomicU64::new(0)),
+            current_operation_id: Arc::new(AtomicU64::new(0)),
+            has_step_started: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    pub fn has_open_repo(&self) -> bool {
-        match self.borrow_repo() {
-            Ok(_) => true,
-            Err(_) => false,
+    fn new_operation_id(&self) -> u64 {
+        self.last_operation_id.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn start_operation(&self, operation_id: u64) {
+        self.current_operation_id.store(operation_id, Ordering::SeqCst);
+        self.has_step_started.store(false, Ordering::SeqCst);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled_through_id.fetch_max(self.last_operation_id.load(Ordering::SeqCst), Ordering::SeqCst);
+    }
+
+    // Cancellable steps call this when they start. The first step keeps the operation's id so a cancel sent while the
+    // operation was waiting still stops it, but later steps (like the refresh after a pull) get a new one so a cancel
+    // meant for an earlier step doesn't stop them.
+    pub fn start_step(&self) {
+        if self.has_step_started.swap(true, Ordering::SeqCst) {
+            self.current_operation_id.store(self.new_operation_id(), Ordering::SeqCst);
         }
     }
 
-    pub fn init_repo(&mut self, json_str: &str) -> Result<()> {
-        let path_value: Value = serde_json::from_str(json_str)?;
-        let path_str = GitManager::get_string_from_serde_string(path_value.as_str())?;
-        self.repo = Some(Repository::init(Path::new(path_str))?);
-        Ok(())
+    pub fn is_cancelled(&self) -> bool {
+        let current_operation_id = self.current_operation_id.load(Ordering::SeqCst);
+        current_operation_id != 0 && current_operation_id <= self.cancelled_through_id.load(Ordering::SeqCst)
     }
 
-    pub fn open_repo(&mut self, json_str: &str) -> Result<()> {
-        let path_value: Value = serde_json::from_str(json_str)?;
-        let path_str = GitManager::get_string_from_serde_string(path_value.as_str())?;
-        self.repo = Some(Repository::open(Path::new(path_str))?);
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(OperationCancelled.into());
+        }
         Ok(())
     }
 
-    pub fn clone_repo(&mut self, json_str: &str) -> Result<()> {
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let clone_url = match json_hm.get("clone_url") {
-            Some(s) => s,
-            None => bail!("clone_url not included in payload from the front-end"),
-        };
-        let clone_path = match json_hm.get("clone_path") {
-            Some(s) => s,
-            None => bail!("clone_path not included in payload from the front-end"),
-        };
+    // libgit2 reports an aborted callback as a generic error, so swap that out for the cancellation.
+    fn map_git_error<E: Into<anyhow::Error>>(&self, e: E) -> anyhow::Error {
+        if self.is_cancelled() {
+            OperationCancelled.into()
+        } else {
+            e.into()
+        }
+    }
+}
 
-        let callbacks = GitManager::get_remote_callbacks();
-        let mut fetch_options = FetchOptions::new();
-        fetch_options.download_tags(AutotagOption::All);
-        fetch_options.remote_callbacks(callbacks);
+// The mutex every operation locks the GitManager through, so each one gets its cancel id before it starts waiting.
+pub struct SharedGitManager {
+    cancel_handle: CancelHandle,
+    git_manager: Mutex<GitManager>,
+}
 
-        let mut repo_builder = RepoBuilder::new();
-        repo_builder.fetch_options(fetch_options);
+impl SharedGitManager {
+    pub fn new(git_manager: GitManager) -> Self {
+        Self {
+            cancel_handle: git_manager.get_cancel_handle(),
+            git_manager: Mutex::new(git_manager),
+        }
+    }
 
-        let project_name = match clone_url.split("/").last() {
-            Some(s) => {
-                &s[..(s.len() - 4)]
-            },
-            None => bail!("Clone url was empty?"),
-        };
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, GitManager>> {
+        let operation_id = self.cancel_handle.new_operation_id();
+        let lock_result = self.git_manager.lock();
+        self.cancel_handle.start_operation(operation_id);
+        lock_result
+    }
+}
 
-        let mut path_buf = PathBuf::from(clone_path);
-        path_buf.push(project_name);
+// Returned when an SSH server's host key isn't in known_hosts yet, so the user can be asked whether to trust it.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnknownHostKey {
+    hostname: String,
+    key_type: String,
+    key_type_short_name: String,
+    fingerprint: String,
+    key: String,
+}
 
-        create_dir_all(path_buf.as_path())?;
+impl fmt::Display for UnknownHostKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The authenticity of host '{}' can't be established. Its {} key fingerprint is {}.", self.hostname, self.key_type_short_name, self.fingerprint)
+    }
+}
 
-        self.repo = Some(repo_builder.clone(clone_url, path_buf.as_path())?);
+impl std::error::Error for UnknownHostKey {}
 
-        Ok(())
+#[derive(Clone, PartialEq)]
+pub struct KnownHostKey {
+    hostname: String,
+    key_type: String,
+    // Base64 encoded, like in known_hosts.
+    key: String,
+}
+
+impl KnownHostKey {
+    fn is_same_key(&self, other: &KnownHostKey) -> bool {
+        self.key_type == other.key_type && self.key == other.key
     }
 
-    fn get_message_without_summary(full_message: &str) -> (String, bool) {
-        let mut separate_pieces: VecDeque<&str> = full_message.split("\r\n\r\n").collect();
-        let mut use_crlf = true;
+    fn get_known_hosts_paths() -> Vec<PathBuf> {
+        let mut paths = vec![];
+        if let Some(base_dirs) = BaseDirs::new() {
+            paths.push(base_dirs.home_dir().join(".ssh").join("known_hosts"));
+        }
+        paths.push(PathBuf::from("/etc/ssh/ssh_known_hosts"));
+        paths
+    }
 
-        if separate_pieces.len() == 1 {
-            separate_pieces = full_message.split("\n\n").collect();
-            use_crlf = false;
+    // Like OpenSSH, hosts on a port other than 22 are listed as "[host]:port".
+    fn get_known_hosts_name(hostname: &str, port: u16) -> String {
+        // Hostnames are case insensitive, and OpenSSH hashes them in lowercase.
+        let hostname = hostname.to_lowercase();
+        if port == 22 {
+            hostname
+        } else {
+            format!("[{}]:{}", hostname, port)
         }
+    }
 
-        // Remove the summary
-        separate_pieces.pop_front();
+    // The port in ssh://host:port/path URLs. scp-like remotes (user@host:path) always use port 22.
+    fn get_ssh_port(url: &str) -> u16 {
+        let after_scheme = match url.find("://") {
+            Some(i) => &url[(i + 3)..],
+            None => return 22,
+        };
+        let authority = match after_scheme.find('/') {
+            Some(i) => &after_scheme[..i],
+            None => after_scheme,
+        };
+        let host_and_port = match authority.rfind('@') {
+            Some(i) => &authority[(i + 1)..],
+            None => authority,
+        };
+        // IPv6 addresses are written in brackets, like [::1]:2222.
+        let port_start_opt = match host_and_port.rfind(']') {
+            Some(i) => host_and_port[i..].find(':').map(|j| i + j),
+            None => host_and_port.rfind(':'),
+        };
+        match port_start_opt {
+            Some(i) => host_and_port[(i + 1)..].parse().unwrap_or(22),
+            None => 22,
+        }
+    }
 
-        if use_crlf {
-            (separate_pieces.make_contiguous().join("\r\n\r\n"), use_crlf)
-        } else {
-            (separate_pieces.make_contiguous().join("\n\n"), use_crlf)
+    // Returns the keys listed for the host, and whether each one is marked as revoked. The hostname is
+    // already in its known_hosts form from get_known_hosts_name().
+    fn find_for_host(hostname: &str) -> Result<Vec<(bool, KnownHostKey)>> {
+        let mut entries = vec![];
+        for path in KnownHostKey::get_known_hosts_paths() {
+            if !path.exists() {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)?;
+            let mut has_hashed_hosts = false;
+            for line in contents.lines() {
+                if let Some((marker_opt, hosts, key_type, key)) = KnownHostKey::parse_known_hosts_line(line) {
+                    if hosts.starts_with('|') {
+                        has_hashed_hosts = true;
+                    } else if KnownHostKey::is_host_match(hosts, hostname) {
+                        KnownHostKey::push_entry(&mut entries, marker_opt, hostname, key_type, key);
+                    }
+                }
+            }
+
+            // Hashed hostnames need HMAC-SHA1 to match, so let OpenSSH look those up.
+            if has_hashed_hosts {
+                let output_result = Command::new("ssh-keygen").arg("-F").arg(hostname).arg("-f").arg(&path).stdin(Stdio::null()).stderr(Stdio::null()).output();
+                if let Ok(output) = output_result {
+                    for line in String::from_utf8_lossy(&output.stdout).lines() {
+                        if let Some((marker_opt, hosts, key_type, key)) = KnownHostKey::parse_known_hosts_line(line) {
+                            if hosts.starts_with('|') {
+                                KnownHostKey::push_entry(&mut entries, marker_opt, hostname, key_type, key);
+                            }
+                        }
+                    }
+                }
+            }
         }
+        Ok(entries)
     }
 
-    fn old_shas_eq_sorted_new_oids(&self, new_oids: &Vec<Oid>) -> bool {
-        // NOTE: This function assumes the new_oids are sorted by date!
-        if self.old_graph_starting_shas.len() == new_oids.len() {
-            for i in 0..self.old_graph_starting_shas.len() {
-                if self.old_graph_starting_shas[i] != new_oids[i].to_string() {
+    fn push_entry(entries: &mut Vec<(bool, KnownHostKey)>, marker_opt: Option<&str>, hostname: &str, key_type: &str, key: &str) {
+        // Certificate authorities aren't supported by libgit2, so those lines are skipped.
+        let is_revoked = match marker_opt {
+            Some("@revoked") => true,
+            Some(_) => return,
+            None => false,
+        };
+        entries.push((is_revoked, KnownHostKey {
+            hostname: String::from(hostname),
+            key_type: String::from(key_type),
+            key: String::from(key),
+        }));
+    }
+
+    // Lines look like "[@marker] hosts key-type base64-key [comment]".
+    fn parse_known_hosts_line(line: &str) -> Option<(Option<&str>, &str, &str, &str)> {
+        let trimmed_line = line.trim();
+        if trimmed_line == "" || trimmed_line.starts_with('#') {
+            return None;
+        }
+        let mut fields = trimmed_line.split_whitespace();
+        let mut first_field = fields.next()?;
+        let mut marker_opt = None;
+        if first_field.starts_with('@') {
+            marker_opt = Some(first_field);
+            first_field = fields.next()?;
+        }
+        let key_type = fields.next()?;
+        let key = fields.next()?;
+        Some((marker_opt, first_field, key_type, key))
+    }
+
+    // Patterns are matched against the whole "[host]:port" form, so those entries only match on their port.
+    fn is_host_match(hosts: &str, hostname: &str) -> bool {
+        let mut is_match = false;
+        for pattern in hosts.split(',') {
+            let (is_negated, pattern) = match pattern.strip_prefix('!') {
+                Some(p) => (true, p),
+                None => (false, pattern),
+            };
+            if KnownHostKey::is_wildcard_match(&pattern.to_lowercase(), hostname) {
+                if is_negated {
                     return false;
                 }
+                is_match = true;
             }
-            return true;
         }
-        false
+        is_match
     }
 
-    pub fn git_revwalk(&mut self, force_refresh: bool) -> Result<Option<Vec<Oid>>> {
-        let mut oid_vec: Vec<Oid> = vec![];
-        // This closure allows self to be borrowed mutably later for setting the new graph starting shas.
-        {
-            let repo = self.borrow_repo()?;
-            for branch_result in repo.branches(None)? {
-                let (branch, _) = branch_result?;
-                match branch.get().target() {
-                    Some(oid) => {
+    fn is_wildcard_match(pattern: &str, s: &str) -> bool {
+        match pattern.chars().next() {
+            None => s == "",
+            Some('*') => (0..=s.len()).filter(|i| s.is_char_boundary(*i)).any(|i| KnownHostKey::is_wildcard_match(&pattern[1..], &s[i..])),
+            Some(c) => match s.chars().next() {
+                Some(sc) if c == '?' || c == sc => KnownHostKey::is_wildcard_match(&pattern[c.len_utf8()..], &s[sc.len_utf8()..]),
+                _ => false,
+            },
+        }
+    }
+
+    fn add_to_known_hosts(&self) -> Result<()> {
+        let base_dirs = match BaseDirs::new() {
+            Some(b) => b,
+            None => bail!("Failed to determine HOME directory on your OS"),
+        };
+        let ssh_dir = base_dirs.home_dir().join(".ssh");
+        create_dir_all(&ssh_dir)?;
+        let known_hosts_path = ssh_dir.join("known_hosts");
+
+        // Don't glue the new entry onto a last line that's missing its newline.
+        let needs_newline = match fs::read(&known_hosts_path) {
+            Ok(contents) => contents.last().map_or(false, |b| *b != b'\n'),
+            Err(_) => false,
+        };
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&known_hosts_path)?;
+        if needs_newline {
+            writeln!(file)?;
+        }
+        writeln!(file, "{} {} {}", self.hostname, self.key_type, self.key)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct HelperCredential {
+    url: String,
+    username: String,
+    password: String,
+}
+
+struct CredentialState {
+    // Used to look up credential helpers, falling back to the global git config when there's no repo yet.
+    git_config_opt: Option<Config>,
+    git_dir_opt: Option<PathBuf>,
+    has_tried_ssh_agent: bool,
+    has_tried_credential_helper: bool,
+    has_tried_stored_cred: bool,
+    // Credentials from a helper that haven't been confirmed to work or not yet.
+    pending_helper_credential_opt: Option<HelperCredential>,
+    // Set when an SSH host key was rejected, since libgit2 only passes along the message.
+    host_key_error_opt: Option<anyhow::Error>,
+}
+
+// Hands out credentials for one remote operation, trying ssh-agent, then git's credential helpers, then the
+// credentials stored by the app. libgit2 asks again whenever the last credentials were rejected.
+#[derive(Clone)]
+pub struct CredentialSession {
+    state: Rc<RefCell<CredentialState>>,
+    session_host_keys: Arc<Mutex<Vec<KnownHostKey>>>,
+    // libgit2 doesn't pass the port to the certificate check, so it's taken from the remote's URL up front.
+    ssh_port: u16,
+    // Checked when libgit2 asks for credentials or a host key check, since those can come before any transfer progress.
+    cancel_handle: CancelHandle,
+}
+
+impl CredentialSession {
+    fn new(repo_opt: Option<&Repository>, url: &str, session_host_keys: Arc<Mutex<Vec<KnownHostKey>>>, cancel_handle: CancelHandle) -> Self {
+        let git_config_opt = match repo_opt {
+            Some(repo) => repo.config().ok(),
+            None => Config::open_default().ok(),
+        };
+        Self {
+            state: Rc::new(RefCell::new(CredentialState {
+                git_config_opt,
+                git_dir_opt: repo_opt.map(|r| r.path().to_path_buf()),
+                has_tried_ssh_agent: false,
+                has_tried_credential_helper: false,
+                has_tried_stored_cred: false,
+                pending_helper_credential_opt: None,
+                host_key_error_opt: None,
+            })),
+            session_host_keys,
+            ssh_port: KnownHostKey::get_ssh_port(url),
+            cancel_handle,
+        }
+    }
+
+    fn get_next_cred(&self, url: &str, username_from_url: Option<&str>, allowed_types: CredentialType) -> Result<Cred, git2::Error> {
+        if self.cancel_handle.is_cancelled() {
+            return Err(git2::Error::from_str("The operation was cancelled."));
+        }
+        let mut state = self.state.borrow_mut();
+
+        // Being asked again means the helper's credentials didn't work.
+        if let Some(helper_credential) = state.pending_helper_credential_opt.take() {
+            CredentialSession::report_to_credential_helper("reject", &helper_credential, &state.git_dir_opt);
+        }
+
+        if allowed_types.contains(CredentialType::SSH_KEY) && !state.has_tried_ssh_agent {
+            state.has_tried_ssh_agent = true;
+            if let Some(username) = username_from_url {
+                if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                    return Ok(cred);
+                }
+            }
+        }
+
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) && !state.has_tried_credential_helper {
+            state.has_tried_credential_helper = true;
+            if let Some(git_config) = &state.git_config_opt {
+                let mut credential_helper = CredentialHelper::new(url);
+                credential_helper.username(username_from_url);
+                credential_helper.config(git_config);
+                if let Some((username, password)) = credential_helper.execute() {
+                    let cred = Cred::userpass_plaintext(&username, &password)?;
+                    state.pending_helper_credential_opt = Some(HelperCredential {
+                        url: String::from(url),
+                        username,
+                        password,
+                    });
+                    return Ok(cred);
+                }
+            }
+        }
+
+        if state.has_tried_stored_cred {
+            return Err(git2::Error::from_str("Authentication failed. Please check your credentials in the menu bar under Security > Set Credentials"));
+        }
+        state.has_tried_stored_cred = true;
+        GitManager::get_stored_cred(url, username_from_url)
+    }
+
+    fn check_certificate(&self, cert: &Cert, hostname: &str) -> Result<CertificateCheckStatus, git2::Error> {
+        if self.cancel_handle.is_cancelled() {
+            return Err(git2::Error::from_str("The operation was cancelled."));
+        }
+        // HTTPS certificates are left to libgit2, and failures get a clearer message in finish().
+        let cert_hostkey = match cert.as_hostkey() {
+            Some(h) => h,
+            None => return Ok(CertificateCheckStatus::CertificatePassthrough),
+        };
+        let (key_bytes, key_type) = match (cert_hostkey.hostkey(), cert_hostkey.hostkey_type()) {
+            (Some(b), Some(t)) if !matches!(t, SshHostKeyType::Unknown) => (b, t),
+            _ => return Ok(CertificateCheckStatus::CertificatePassthrough),
+        };
+        let hostname = &*KnownHostKey::get_known_hosts_name(hostname, self.ssh_port);
+        let presented_key = KnownHostKey {
+            hostname: String::from(hostname),
+            key_type: String::from(key_type.name()),
+            key: STANDARD.encode(key_bytes),
+        };
+        let fingerprint = match cert_hostkey.hash_sha256() {
+            Some(hash) => format!("SHA256:{}", STANDARD_NO_PAD.encode(hash)),
+            None => String::from("unavailable"),
+        };
+
+        if self.session_host_keys.lock().unwrap().contains(&presented_key) {
+            return Ok(CertificateCheckStatus::CertificateOk);
+        }
+
+        let host_key_error = match KnownHostKey::find_for_host(hostname) {
+            Ok(entries) => {
+                if entries.iter().any(|(is_revoked, k)| *is_revoked && k.is_same_key(&presented_key)) {
+                    anyhow::anyhow!("The SSH host key for '{}' ({} key fingerprint {}) has been revoked in your known_hosts file, so the connection was refused.", hostname, key_type.short_name(), fingerprint)
+                } else if entries.iter().any(|(is_revoked, k)| !*is_revoked && k.is_same_key(&presented_key)) {
+                    return Ok(CertificateCheckStatus::CertificateOk);
+                } else if entries.iter().any(|(is_revoked, k)| !*is_revoked && k.key_type == presented_key.key_type) {
+                    anyhow::anyhow!("WARNING: The SSH host key for '{}' has changed! Someone could be intercepting the connection, or the server's key may have been replaced. The {} key fingerprint sent by the server is {}. If you're sure the change is expected, remove the old key for '{}' from your known_hosts file and try again.", hostname, key_type.short_name(), fingerprint, hostname)
+                } else {
+                    UnknownHostKey {
+                        hostname: String::from(hostname),
+                        key_type: presented_key.key_type.clone(),
+                        key_type_short_name: String::from(key_type.short_name()),
+                        fingerprint,
+                        key: presented_key.key.clone(),
+                    }.into()
+                }
+            },
+            Err(e) => anyhow::anyhow!("Failed to read known_hosts to verify the SSH host key for '{}': {}", hostname, e),
+        };
+        let message = host_key_error.to_string();
+        self.state.borrow_mut().host_key_error_opt = Some(host_key_error);
+        Err(git2::Error::from_str(&message))
+    }
+
+    // Call with the result of the remote operation, so a credential helper learns whether its credentials worked
+    // and host key or certificate problems are reported clearly.
+    fn finish<T>(&self, result: Result<T, git2::Error>) -> Result<T> {
+        let mut state = self.state.borrow_mut();
+        if let Some(helper_credential) = state.pending_helper_credential_opt.take() {
+            match &result {
+                Ok(_) => CredentialSession::report_to_credential_helper("approve", &helper_credential, &state.git_dir_opt),
+                Err(e) => {
+                    if e.code() == ErrorCode::Auth {
+                        CredentialSession::report_to_credential_helper("reject", &helper_credential, &state.git_dir_opt);
+                    }
+                },
+            };
+        }
+        match result {
+            Ok(t) => Ok(t),
+            Err(e) => {
+                if self.cancel_handle.is_cancelled() {
+                    return Err(OperationCancelled.into());
+                }
+                if let Some(host_key_error) = state.host_key_error_opt.take() {
+                    return Err(host_key_error);
+                }
+                if e.code() == ErrorCode::Certificate || e.class() == ErrorClass::Ssl {
+                    bail!("The server's HTTPS certificate couldn't be verified ({}). It may be self-signed, expired, or issued for a different host. Check the remote's URL, or add the certificate's authority to your system's trusted certificates.", e.message());
+                }
+                Err(e.into())
+            },
+        }
+    }
+
+    // Like git itself, failures to talk to the helper are ignored.
+    fn report_to_credential_helper(action: &str, helper_credential: &HelperCredential, git_dir_opt: &Option<PathBuf>) {
+        let mut command = Command::new("git");
+        if let Some(git_dir) = git_dir_opt {
+            command.arg("-C").arg(git_dir);
+        }
+        command.args(["credential", action]).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null());
+        if let Ok(mut child) = command.spawn() {
+            if let Some(stdin) = child.stdin.as_mut() {
+                let _ = write!(stdin, "url={}\nusername={}\npassword={}\n\n", helper_credential.url, helper_credential.username, helper_credential.password);
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+// How many commits are walked or looked up between cancellation checks.
+pub const CANCEL_CHECK_INTERVAL: usize = 1000;
+
+pub type RemoteProgressHandler = Arc<dyn Fn(RemoteProgress) + Send + Sync>;
+
+// How often frequent progress updates are passed on, so the front-end isn't flooded.
+const REMOTE_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// libgit2 doesn't time out by default, so an unreachable remote would hold the GitManager's lock indefinitely.
+// The connect timeout applies to both HTTP(S) and SSH, but libgit2 only applies the read timeout to HTTP(S).
+const SERVER_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const SERVER_TIMEOUT: Duration = Duration::from_secs(120);
+// git2 0.18 doesn't wrap these options, so their values come from libgit2's git_libgit2_opt_t.
+const GIT_OPT_SET_SERVER_CONNECT_TIMEOUT: c_int = 39;
+const GIT_OPT_SET_SERVER_TIMEOUT: c_int = 41;
+
+#[derive(Clone, Serialize)]
+pub struct TagInfo {
+    name: String,
+    full_name: String,
+    is_annotated: bool,
+    // The object the tag points at directly, which is usually but not always a commit.
+    target_sha: String,
+    target_type: String,
+    // The commit the tag ultimately points at, if any.
+    commit_sha: Option<String>,
+    // Only set for annotated tags.
+    tagger_name: Option<String>,
+    tagger_email: Option<String>,
+    tagger_time: Option<i64>,
+    message: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushTagsOptions {
+    // When empty, all tags are pushed.
+    #[serde(default)]
+    tag_full_names: Vec<String>,
+    #[serde(default)]
+    is_force_push: bool,
+    selected_remote: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct DeleteTagOptions {
+    tag_name: String,
+    // When set, the tag is also deleted from this remote.
+    #[serde(default)]
+    remote_name: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SubmoduleInfo {
+    name: String,
+    path: String,
+    url: Option<String>,
+    branch: Option<String>,
+    // The commit recorded in HEAD, in the index, and the one actually checked out in the submodule.
+    recorded_sha: Option<String>,
+    index_sha: Option<String>,
+    checked_out_sha: Option<String>,
+    is_initialized: bool,
+    is_checked_out: bool,
+    is_commit_changed: bool,
+    is_dirty: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WorktreeInfo {
+    // The main worktree doesn't have a name.
+    name: Option<String>,
+    path: String,
+    head_branch: Option<String>,
+    head_sha: Option<String>,
+    is_main: bool,
+    is_current: bool,
+    is_locked: bool,
+    lock_reason: Option<String>,
+    // Set when the worktree's directory is gone, so it can be pruned.
+    is_prunable: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct CommitSearchResult {
+    sha: String,
+    // The commit's row in the graph, or None when it's past the pages loaded so far.
+    position: Option<usize>,
+    summary: String,
+    author_name: String,
+    author_time: i64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct FileHistoryEntry {
+    sha: String,
+    summary: String,
+    author_name: String,
+    author_time: i64,
+    path: String,
+    old_path: Option<String>,
+    status: String,
+    file_info: FileInfo,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BlameLineInfo {
+    line_number: usize,
+    file_type: String,
+    content: String,
+    hunk_index: usize,
+    sha: String,
+    author_name: String,
+    author_time: i64,
+    summary: String,
+    orig_line_number: usize,
+    orig_path: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BlameHunkInfo {
+    sha: String,
+    author_name: String,
+    author_time: i64,
+    summary: String,
+    start_line: usize,
+    line_count: usize,
+    orig_start_line: usize,
+    orig_path: String,
+    is_boundary: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BlameInfo {
+    file_path: String,
+    sha: String,
+    lines: Vec<BlameLineInfo>,
+    hunks: Vec<BlameHunkInfo>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct CommitInfo {
+    sha: String,
+    summary: String,
+    message: String,
+    author_name: String,
+    author_time: i64,
+    committer_name: String,
+    committer_time: i64,
+    changed_files: Vec<ParseableDiffDelta>,
+}
+
+impl CommitInfo {
+    pub fn from_commit(commit: Commit, repo: &Repository) -> Result<Self> {
+        let author_signature = commit.author();
+        let author_name = String::from(GitManager::get_utf8_string(author_signature.name(), "Author Name")?);
+        let author_time = author_signature.when().seconds();
+
+        let committer_signature = commit.committer();
+        let committer_name = String::from(GitManager::get_utf8_string(committer_signature.name(), "Committer Name")?);
+        let committer_time = committer_signature.when().seconds();
+
+        let diff = get_commit_changes(&commit, repo)?;
+        let parseable_diff_delta = get_parseable_diff_delta(diff)?;
+
+        let new_commit_info = Self {
+            sha: commit.id().to_string(),
+            summary: html_escape::encode_text(&String::from(GitManager::get_utf8_string(commit.summary(), "Commit Summary")?)).parse()?,
+            message: html_escape::encode_text(&String::from(GitManager::get_utf8_string(commit.message(), "Commit Message")?)).parse()?,
+            author_name: html_escape::encode_text(&author_name).parse()?,
+            author_time,
+            committer_name: html_escape::encode_text(&committer_name).parse()?,
+            committer_time,
+            changed_files: parseable_diff_delta,
+        };
+
+        Ok(new_commit_info)
+    }
+}
+
+fn get_commit_changes<'a, 'b>(commit: &'a Commit, repo: &'b Repository) -> Result<Diff<'b>> {
+    let commit_tree = commit.tree()?;
+
+    let mut diff_opt = None;
+    for parent_commit in commit.parents() {
+        let mut diff = repo.diff_tree_to_tree(Some(&parent_commit.tree()?), Some(&commit_tree), None)?;
+        GitManager::set_diff_find_similar(&mut diff)?;
+        // For merge commits, the diff between a merge commit and the parent from the branch that was merged will be empty,
+        // so find the diff that's populated.
+        if diff.stats()?.files_changed() > 0 {
+            return Ok(diff);
+        }
+        diff_opt = Some(diff);
+    }
+
+    // If there are parents but no diff was returned earlier, then the commit is empty and should return the empty diff.
+    if let Some(d) = diff_opt {
+        return Ok(d);
+    }
+
+    // If there are no parents, get the diff between this commit and nothing.
+    let mut diff = repo.diff_tree_to_tree(None, Some(&commit_tree), None)?;
+    GitManager::set_diff_find_similar(&mut diff)?;
+
+    Ok(diff)
+}
+
+fn get_stash_diff<'a, 'b>(stash_commit: &'a Commit, repo: &'b Repository) -> Result<Diff<'b>> {
+    // A stash commit's first parent is the commit it was made on, and its third parent (if any) holds the untracked files.
+    let base_tree = stash_commit.parent(0)?.tree()?;
+    let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&stash_commit.tree()?), None)?;
+    if stash_commit.parent_count() > 2 {
+        let untracked_diff = repo.diff_tree_to_tree(None, Some(&stash_commit.parent(2)?.tree()?), None)?;
+        diff.merge(&untracked_diff)?;
+    }
+    GitManager::set_diff_find_similar(&mut diff)?;
+
+    Ok(diff)
+}
+
+pub enum GraphChange {
+    // The graph has to be laid out from scratch, starting with the first page of these oids.
+    Full(Vec<Oid>),
+    // Only the given number of commits were removed from the top of the graph and these oids were added above the rest.
+    TopOnly(usize, Vec<Oid>),
+}
+
+pub struct GitManager {
+    repo: Option<Repository>,
+    old_graph_starting_shas: Vec<String>,
+    graph_oids: Vec<Oid>,
+    graph_loaded_count: usize,
+    graph_lane_state: GraphLaneState,
+    current_local_offset: UtcOffset,
+    remote_progress_handler_opt: Option<RemoteProgressHandler>,
+    cancel_handle: CancelHandle,
+    // SSH host keys the user chose to trust without adding them to known_hosts.
+    session_host_keys: Arc<Mutex<Vec<KnownHostKey>>>,
+}
+
+impl GitManager {
+    pub fn new(current_local_offset: UtcOffset) -> Self {
+        GitManager::set_server_timeouts();
+        Self {
+            repo: None,
+            old_graph_starting_shas: vec![],
+            graph_oids: vec![],
+            graph_loaded_count: 0,
+            graph_lane_state: GraphLaneState::new(),
+            current_local_offset,
+            remote_progress_handler_opt: None,
+            cancel_handle: CancelHandle::new(),
+            session_host_keys: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    fn set_server_timeouts() {
+        libgit2_sys::init();
+        unsafe {
+            libgit2_sys::git_libgit2_opts(GIT_OPT_SET_SERVER_CONNECT_TIMEOUT, SERVER_CONNECT_TIMEOUT.as_millis() as c_int);
+            libgit2_sys::git_libgit2_opts(GIT_OPT_SET_SERVER_TIMEOUT, SERVER_TIMEOUT.as_millis() as c_int);
+        }
+    }
+
+    fn new_credential_session(&self, repo_opt: Option<&Repository>, url: &str) -> CredentialSession {
+        CredentialSession::new(repo_opt, url, self.session_host_keys.clone(), self.cancel_handle.clone())
+    }
+
+    // Pushes go to the push URL when one is set.
+    fn get_remote_push_url<'a>(remote: &'a Remote) -> &'a str {
+        remote.pushurl().or(remote.url()).unwrap_or("")
+    }
+
+    pub fn get_cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    pub fn borrow_cancel_handle(&self) -> &CancelHandle {
+        &self.cancel_handle
+    }
+
+    pub fn set_remote_progress_handler<F>(&mut self, remote_progress_handler: F) where F: Fn(RemoteProgress) + Send + Sync + 'static {
+        self.remote_progress_handler_opt = Some(Arc::new(remote_progress_handler));
+    }
+
+    pub fn get_utf8_string<'a, 'b>(value: Option<&'a str>, str_name_type: &'b str) -> Result<&'a str> {
+        match value {
+            Some(n) => Ok(n),
+            None => bail!(format!("{} uses invalid utf-8!", str_name_type)),
+        }
+    }
+
+    fn get_string_from_serde_string(value: Option<&str>) -> Result<&str> {
+        match value {
+            Some(s) => Ok(s),
+            None => bail!("Invalid JSON String!"),
+        }
+    }
+
+    pub fn borrow_current_local_offset(&self) -> &UtcOffset {
+        &self.current_local_offset
+    }
+
+    pub fn borrow_repo(&self) -> Result<&Repository> {
+        let repo_temp_opt = &self.repo;
+        match repo_temp_opt {
+            Some(repo) => Ok(repo),
+            None => bail!("No repo loaded to perform operation on."),
+        }
+    }
+
+    pub fn borrow_repo_mut(&mut self) -> Result<&mut Repository> {
+        let repo_temp_opt = &mut self.repo;
+        match repo_temp_opt {
+            Some(repo) => Ok(repo),
+            None => bail!("No repo loaded to perform operation on."),
+        }
+    }
+
+    pub fn has_open_repo(&self) -> bool {
+        match self.borrow_repo() {
+            Ok(_) => true,
+            Err(_) => false,
+        }
+    }
+
+    fn get_signature(&self) -> Result<Signature<'static>> {
+        let repo = self.borrow_repo()?;
+
+        // A repo specific identity is written to the repo's own git config, so it wins over everything else.
+        if let Ok(local_config) = repo.config()?.open_level(ConfigLevel::Local) {
+            if let (Ok(name), Ok(email)) = (local_config.get_string("user.name"), local_config.get_string("user.email")) {
+                return Ok(Signature::now(&*name, &*email)?);
+            }
+        }
+
+        let config = config_manager::get_config()?;
+        if let Some(profile_name) = config.borrow_default_identity_profile() {
+            if let Some(profile) = config.get_identity_profile(profile_name) {
+                return Ok(Signature::now(profile.borrow_name(), profile.borrow_email())?);
+            }
+        }
+
+        match repo.signature() {
+            Ok(s) => Ok(s),
+            Err(_) => bail!("No commit identity is set. Please add an identity profile, or set user.name and user.email in your git config."),
+        }
+    }
+
+    pub fn init_repo(&mut self, json_str: &str) -> Result<()> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path_str = GitManager::get_string_from_serde_string(path_value.as_str())?;
+        self.repo = Some(Repository::init(Path::new(path_str))?);
+        Ok(())
+    }
+
+    pub fn open_repo(&mut self, json_str: &str) -> Result<()> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path_str = GitManager::get_string_from_serde_string(path_value.as_str())?;
+        self.repo = Some(Repository::open(Path::new(path_str))?);
+        Ok(())
+    }
+
+    pub fn clone_repo(&mut self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let clone_url = match json_hm.get("clone_url") {
+            Some(s) => s,
+            None => bail!("clone_url not included in payload from the front-end"),
+        };
+        let clone_path = match json_hm.get("clone_path") {
+            Some(s) => s,
+            None => bail!("clone_path not included in payload from the front-end"),
+        };
+
+        self.cancel_handle.start_step();
+        let credential_session = self.new_credential_session(None, clone_url);
+        let callbacks = self.get_remote_callbacks_with_progress(&credential_session, "clone", "origin");
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.download_tags(AutotagOption::All);
+        fetch_options.remote_callbacks(callbacks);
+
+        let mut repo_builder = RepoBuilder::new();
+        repo_builder.fetch_options(fetch_options);
+
+        let project_name = match clone_url.split("/").last() {
+            Some(s) => {
+                &s[..(s.len() - 4)]
+            },
+            None => bail!("Clone url was empty?"),
+        };
+
+        let mut path_buf = PathBuf::from(clone_path);
+        path_buf.push(project_name);
+
+        create_dir_all(path_buf.as_path())?;
+
+        match credential_session.finish(repo_builder.clone(clone_url, path_buf.as_path())) {
+            Ok(r) => self.repo = Some(r),
+            Err(e) => {
+                // Don't leave a half-cloned repo behind.
+                if self.cancel_handle.is_cancelled() {
+                    fs::remove_dir_all(path_buf.as_path())?;
+                }
+                return Err(self.cancel_handle.map_git_error(e));
+            },
+        };
+
+        // Older front-ends don't send this, so it's optional.
+        let recursive = match json_hm.get("recursive") {
+            Some(s) => s == "true",
+            None => false,
+        };
+        if recursive {
+            self.update_submodules(self.borrow_repo()?, "", true, true)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_message_without_summary(full_message: &str) -> (String, bool) {
+        let mut separate_pieces: VecDeque<&str> = full_message.split("\r\n\r\n").collect();
+        let mut use_crlf = true;
+
+        if separate_pieces.len() == 1 {
+            separate_pieces = full_message.split("\n\n").collect();
+            use_crlf = false;
+        }
+
+        // Remove the summary
+        separate_pieces.pop_front();
+
+        if use_crlf {
+            (separate_pieces.make_contiguous().join("\r\n\r\n"), use_crlf)
+        } else {
+            (separate_pieces.make_contiguous().join("\n\n"), use_crlf)
+        }
+    }
+
+    fn old_shas_eq_sorted_new_oids(&self, new_oids: &Vec<Oid>) -> bool {
+        // NOTE: This function assumes the new_oids are sorted by date!
+        if self.old_graph_starting_shas.len() == new_oids.len() {
+            for i in 0..self.old_graph_starting_shas.len() {
+                if self.old_graph_starting_shas[i] != new_oids[i].to_string() {
+                    return false;
+                }
+            }
+            return true;
+        }
+        false
+    }
+
+    fn get_graph_starting_oids(&self) -> Result<Vec<Oid>> {
+        let mut oid_vec: Vec<Oid> = vec![];
+        let repo = self.borrow_repo()?;
+        let graph_scope = GraphScope::load(repo)?;
+
+        let branch_type_opt = match graph_scope.scope.as_str() {
+            "all" => Some(None),
+            "local" => Some(Some(BranchType::Local)),
+            "current" | "selected" => None,
+            _ => bail!("Graph scope '{}' isn't valid. Choices are 'all', 'local', 'current', or 'selected'", graph_scope.scope),
+        };
+        if let Some(branch_type) = branch_type_opt {
+            for branch_result in repo.branches(branch_type)? {
+                let (branch, _) = branch_result?;
+                match branch.get().target() {
+                    Some(oid) => {
+                        if !oid_vec.contains(&oid) {
+                            oid_vec.push(oid);
+                        }
+                    },
+                    None => (),
+                };
+            };
+        }
+
+        if graph_scope.scope == "selected" {
+            for ref_name in &graph_scope.selected_branches {
+                // Selected branches that have since been deleted are skipped rather than failing the whole graph.
+                let reference = match repo.find_reference(ref_name) {
+                    Ok(r) => r,
+                    Err(e) if e.code() == ErrorCode::NotFound => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                if let Some(oid) = reference.target() {
+                    if !oid_vec.contains(&oid) {
+                        oid_vec.push(oid);
+                    }
+                }
+            }
+        }
+
+        if graph_scope.include_tags {
+            for reference_result in repo.references_glob("refs/tags/*")? {
+                // Tags can point to trees and blobs too, those aren't part of the graph.
+                if let Ok(commit) = reference_result?.peel_to_commit() {
+                    if !oid_vec.contains(&commit.id()) {
+                        oid_vec.push(commit.id());
+                    }
+                }
+            }
+        }
+
+        if graph_scope.include_stashes {
+            match repo.reflog("refs/stash") {
+                Ok(reflog) => {
+                    for reflog_entry in reflog.iter() {
+                        let oid = reflog_entry.id_new();
+                        if !oid_vec.contains(&oid) {
+                            oid_vec.push(oid);
+                        }
+                    }
+                },
+                Err(e) if e.code() == ErrorCode::NotFound => (),
+                Err(e) => return Err(e.into()),
+            };
+        }
+
+        // HEAD is included in every scope so the checked out commit is never missing from the graph.
+        if graph_scope.scope != "all" || repo.head_detached()? {
+            match repo.head() {
+                Ok(head_ref) => {
+                    if let Some(oid) = head_ref.target() {
                         if !oid_vec.contains(&oid) {
                             oid_vec.push(oid);
                         }
-                    },
-                    None => (),
-                };
+                    }
+                },
+                Err(e) if e.code() == ErrorCode::UnbornBranch => (),
+                Err(e) => return Err(e.into()),
+            };
+        }
+
+        // Sort Oids by date first
+        oid_vec.sort_by(|a, b| {
+            repo.find_commit(*b).unwrap().time().seconds().partial_cmp(&repo.find_commit(*a).unwrap().time().seconds()).unwrap()
+        });
+
+        Ok(oid_vec)
+    }
+
+    // Finds how the top of the graph changed when the only difference from the old starting oids is at the top,
+    // so committing, amending, or fetching doesn't have to walk and lay out the entire history again.
+    fn get_graph_top_change(&self, new_oids: &Vec<Oid>) -> Result<Option<(usize, Vec<Oid>)>> {
+        if self.graph_loaded_count == 0 || self.old_graph_starting_shas.is_empty() {
+            return Ok(None);
+        }
+
+        let repo = self.borrow_repo()?;
+        let mut old_oids: Vec<Oid> = vec![];
+        for old_sha in &self.old_graph_starting_shas {
+            let old_oid = Oid::from_str(old_sha)?;
+            // If an old starting commit is gone, there's no way to tell what was removed.
+            if repo.find_commit(old_oid).is_err() {
+                return Ok(None);
+            }
+            old_oids.push(old_oid);
+        }
+
+        let mut added_revwalk = repo.revwalk()?;
+        for oid in new_oids {
+            added_revwalk.push(*oid)?;
+        }
+        for oid in &old_oids {
+            added_revwalk.hide(*oid)?;
+        }
+        added_revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+        let mut added_oids: Vec<Oid> = vec![];
+        for commit_oid_result in added_revwalk {
+            added_oids.push(commit_oid_result?);
+        }
+
+        let mut removed_revwalk = repo.revwalk()?;
+        for oid in &old_oids {
+            removed_revwalk.push(*oid)?;
+        }
+        for oid in new_oids {
+            removed_revwalk.hide(*oid)?;
+        }
+        let mut removed_oids: Vec<Oid> = vec![];
+        for commit_oid_result in removed_revwalk {
+            removed_oids.push(commit_oid_result?);
+        }
+
+        // The removed commits have to be exactly the first rows of what's already loaded, otherwise rows in the middle
+        // of the graph would have to be taken out.
+        if removed_oids.len() > self.graph_loaded_count {
+            return Ok(None);
+        }
+        for oid in &self.graph_oids[..removed_oids.len()] {
+            if !removed_oids.contains(oid) {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some((removed_oids.len(), added_oids)))
+    }
+
+    pub fn git_revwalk(&mut self, force_refresh: bool) -> Result<Option<GraphChange>> {
+        self.cancel_handle.start_step();
+        let oid_vec = self.get_graph_starting_oids()?;
+
+        if force_refresh {
+            self.old_graph_starting_shas = vec![];
+        }
+
+        if self.old_shas_eq_sorted_new_oids(&oid_vec) {
+            return Ok(None);
+        }
+
+        let graph_top_change_opt = self.get_graph_top_change(&oid_vec)?;
+
+        // If you've reached here, the old and new starting oids are different. Update the old and perform the revwalk.
+        self.old_graph_starting_shas = oid_vec.iter().map(|new_oid| {
+            new_oid.to_string()
+        }).collect();
+
+        // New commits can only be descendants of, or unrelated to, the ones already in the graph, so they go above them.
+        if let Some((removed_count, added_oids)) = graph_top_change_opt {
+            let mut graph_oids = added_oids.clone();
+            graph_oids.extend_from_slice(&self.graph_oids[removed_count..]);
+            self.graph_oids = graph_oids;
+            self.graph_loaded_count = self.graph_loaded_count - removed_count + added_oids.len();
+            return Ok(Some(GraphChange::TopOnly(removed_count, added_oids)));
+        }
+
+        let repo = self.borrow_repo()?;
+        let mut revwalk = repo.revwalk()?;
+
+        for oid in oid_vec {
+            revwalk.push(oid)?;
+        }
+        revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+
+        // The whole walk is kept so later pages of the graph can continue from where the last page ended.
+        let mut graph_oids: Vec<Oid> = vec![];
+        let mut is_cancelled = false;
+        for (i, commit_oid_result) in revwalk.enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && self.cancel_handle.is_cancelled() {
+                is_cancelled = true;
+                break;
+            }
+            graph_oids.push(commit_oid_result?);
+        }
+        if is_cancelled {
+            self.reset_graph_state();
+            return Err(OperationCancelled.into());
+        }
+        self.graph_oids = graph_oids;
+        self.graph_loaded_count = 0;
+        self.graph_lane_state = GraphLaneState::new();
+
+        Ok(Some(GraphChange::Full(self.get_next_graph_page()?)))
+    }
+
+    // Forgets the loaded graph, so the next refresh lays it out from scratch.
+    pub fn reset_graph_state(&mut self) {
+        self.old_graph_starting_shas = vec![];
+        self.graph_oids = vec![];
+        self.graph_loaded_count = 0;
+        self.graph_lane_state = GraphLaneState::new();
+    }
+
+    pub fn get_next_graph_page(&mut self) -> Result<Vec<Oid>> {
+        let preferences = config_manager::get_config()?;
+        let limit_commits = match preferences.borrow_limit_commits() {
+            Some(b) => b.clone(),
+            None => bail!("limit_commits not present in config file!"),
+        };
+        let commit_count = match preferences.borrow_commit_count() {
+            Some(i) => i.clone(),
+            None => bail!("commit_count not present in config file!"),
+        };
+
+        // When limiting commits, the commit count is the size of each page rather than a hard cap.
+        let page_start = self.graph_loaded_count;
+        let page_end = if limit_commits {
+            self.graph_oids.len().min(page_start + commit_count)
+        } else {
+            self.graph_oids.len()
+        };
+        self.graph_loaded_count = page_end;
+
+        Ok(self.graph_oids[page_start..page_end].to_vec())
+    }
+
+    pub fn unload_graph_page(&mut self, page_len: usize) {
+        self.graph_loaded_count -= page_len;
+    }
+
+    pub fn get_graph_loaded_count(&self) -> usize {
+        self.graph_loaded_count
+    }
+
+    pub fn has_more_graph_commits(&self) -> bool {
+        self.graph_loaded_count < self.graph_oids.len()
+    }
+
+    pub fn borrow_graph_lane_state_mut(&mut self) -> &mut GraphLaneState {
+        &mut self.graph_lane_state
+    }
+
+    pub fn borrow_graph_lane_state(&self) -> &GraphLaneState {
+        &self.graph_lane_state
+    }
+
+    pub fn get_graph_scope(&self) -> Result<GraphScope> {
+        let repo = self.borrow_repo()?;
+        GraphScope::load(repo)
+    }
+
+    pub fn set_graph_scope(&self, json_str: &str) -> Result<()> {
+        let graph_scope: GraphScope = serde_json::from_str(json_str)?;
+        if !["all", "local", "current", "selected"].contains(&&*graph_scope.scope) {
+            bail!("Graph scope '{}' isn't valid. Choices are 'all', 'local', 'current', or 'selected'", graph_scope.scope);
+        }
+
+        let repo = self.borrow_repo()?;
+        graph_scope.save(repo)?;
+
+        Ok(())
+    }
+
+    pub fn get_commit_info(&self, json_str: &str) -> Result<CommitInfo> {
+        let sha_value: Value = serde_json::from_str(json_str)?;
+        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+        let commit_info = CommitInfo::from_commit(commit, repo)?;
+
+        Ok(commit_info)
+    }
+
+    fn get_optional_time_from_json(json_hm: &HashMap<String, String>, key: &str) -> Result<Option<i64>> {
+        match json_hm.get(key) {
+            Some(s) => {
+                if s == "" {
+                    Ok(None)
+                } else {
+                    Ok(Some(s.parse::<i64>()?))
+                }
+            },
+            None => bail!("{} not included in payload from front-end.", key),
+        }
+    }
+
+    fn does_signature_match(signature: &Signature, search_text: &str) -> bool {
+        let name = signature.name().unwrap_or("").to_lowercase();
+        let email = signature.email().unwrap_or("").to_lowercase();
+        name.contains(search_text) || email.contains(search_text)
+    }
+
+    fn does_commit_touch_path(&self, commit: &Commit, path: &str) -> Result<bool> {
+        let repo = self.borrow_repo()?;
+        let mut diff_options = DiffOptions::new();
+        diff_options.pathspec(path);
+        let parent_tree_opt = match commit.parents().next() {
+            Some(c) => Some(c.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree_opt.as_ref(), Some(&commit.tree()?), Some(&mut diff_options))?;
+        Ok(diff.deltas().len() > 0)
+    }
+
+    pub fn search_commits(&self, json_str: &str) -> Result<Vec<CommitSearchResult>> {
+        self.cancel_handle.start_step();
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let author = match json_hm.get("author") {
+            Some(s) => s.to_lowercase(),
+            None => bail!("author not included in payload from front-end."),
+        };
+        let committer = match json_hm.get("committer") {
+            Some(s) => s.to_lowercase(),
+            None => bail!("committer not included in payload from front-end."),
+        };
+        let message = match json_hm.get("message") {
+            Some(s) => s,
+            None => bail!("message not included in payload from front-end."),
+        };
+        let is_regex = match json_hm.get("is_regex") {
+            Some(s) => s == "true",
+            None => bail!("is_regex not included in payload from front-end."),
+        };
+        let sha_prefix = match json_hm.get("sha_prefix") {
+            Some(s) => s.to_lowercase(),
+            None => bail!("sha_prefix not included in payload from front-end."),
+        };
+        let path = match json_hm.get("path") {
+            Some(s) => s,
+            None => bail!("path not included in payload from front-end."),
+        };
+        // Dates are in seconds since the unix epoch, like the commit times sent to the front-end.
+        let since_opt = GitManager::get_optional_time_from_json(&json_hm, "since")?;
+        let until_opt = GitManager::get_optional_time_from_json(&json_hm, "until")?;
+
+        let message_regex_opt = if is_regex && message != "" {
+            match Regex::new(message) {
+                Ok(r) => Some(r),
+                Err(e) => bail!("Message search isn't a valid regular expression: {}", e),
+            }
+        } else {
+            None
+        };
+        let lowercase_message = message.to_lowercase();
+
+        // Search the graph's own walk so positions line up with the rows it's showing.
+        let repo = self.borrow_repo()?;
+        let mut search_results = vec![];
+        for (i, oid) in self.graph_oids.iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 {
+                self.cancel_handle.check()?;
+            }
+            let oid = *oid;
+            if sha_prefix != "" && !oid.to_string().starts_with(&*sha_prefix) {
+                continue;
+            }
+
+            let commit = repo.find_commit(oid)?;
+            let commit_time = commit.time().seconds();
+            if since_opt.map_or(false, |since| commit_time < since) || until_opt.map_or(false, |until| commit_time > until) {
+                continue;
+            }
+            if author != "" && !GitManager::does_signature_match(&commit.author(), &*author) {
+                continue;
+            }
+            if committer != "" && !GitManager::does_signature_match(&commit.committer(), &*committer) {
+                continue;
+            }
+            if message != "" {
+                let commit_message = commit.message().unwrap_or("").trim_end();
+                let is_message_match = match &message_regex_opt {
+                    Some(r) => r.is_match(commit_message),
+                    None => commit_message.to_lowercase().contains(&*lowercase_message),
+                };
+                if !is_message_match {
+                    continue;
+                }
+            }
+            // Checking the path is the slowest filter, so it's done last.
+            if path != "" && !self.does_commit_touch_path(&commit, path)? {
+                continue;
+            }
+
+            let author_signature = commit.author();
+            search_results.push(CommitSearchResult {
+                sha: oid.to_string(),
+                position: if i < self.graph_loaded_count { Some(i) } else { None },
+                summary: html_escape::encode_text(GitManager::get_utf8_string(commit.summary(), "Commit Summary")?).parse()?,
+                author_name: html_escape::encode_text(GitManager::get_utf8_string(author_signature.name(), "Author Name")?).parse()?,
+                author_time: author_signature.when().seconds(),
+            });
+        }
+
+        Ok(search_results)
+    }
+
+    fn has_conflicts(&self) -> Result<bool> {
+        let unstaged_diff = self.get_unstaged_changes()?;
+        let staged_diff = self.get_staged_changes()?;
+
+        for delta in unstaged_diff.deltas() {
+            if delta.status() == Delta::Conflicted {
+                return Ok(true);
+            }
+        }
+
+        for delta in staged_diff.deltas() {
+            if delta.status() == Delta::Conflicted {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn get_conflict_info_list(&self) -> Result<Vec<ConflictInfo>> {
+        let repo = self.borrow_repo()?;
+        let mut index = repo.index()?;
+        // Make sure conflicts resolved outside of the app are picked up.
+        index.read(false)?;
+
+        let mut conflict_info_list = vec![];
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            // Each side may be missing (e.g. deleted on one side), so use whichever path is available.
+            let path_bytes = match (&conflict.our, &conflict.their, &conflict.ancestor) {
+                (Some(entry), _, _) | (None, Some(entry), _) | (None, None, Some(entry)) => entry.path.clone(),
+                (None, None, None) => continue,
+            };
+            conflict_info_list.push(ConflictInfo {
+                path: String::from_utf8(path_bytes)?,
+                ancestor_sha: conflict.ancestor.map(|e| e.id.to_string()),
+                our_sha: conflict.our.map(|e| e.id.to_string()),
+                their_sha: conflict.their.map(|e| e.id.to_string()),
+            });
+        }
+
+        Ok(conflict_info_list)
+    }
+
+    pub fn get_conflicts(&self) -> Result<ConflictsInfo> {
+        let repo = self.borrow_repo()?;
+
+        let operation = if self.is_interactive_rebasing()? {
+            Some(String::from("rebase"))
+        } else {
+            match repo.state() {
+                RepositoryState::Merge => Some(String::from("merge")),
+                RepositoryState::Revert | RepositoryState::RevertSequence => Some(String::from("revert")),
+                RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some(String::from("cherrypick")),
+                RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => Some(String::from("rebase")),
+                _ => None,
+            }
+        };
+
+        Ok(ConflictsInfo {
+            operation,
+            conflicts: self.get_conflict_info_list()?,
+        })
+    }
+
+    // Merges, cherrypicks, and aborts would clear the state the interactive rebase needs to continue.
+    fn bail_if_interactive_rebasing(&self, operation_name: &str) -> Result<()> {
+        if self.is_interactive_rebasing()? {
+            bail!("Can't {} while an interactive rebase is in progress. Continue or abort the rebase first.", operation_name);
+        }
+        Ok(())
+    }
+
+    fn bail_if_conflicted(&self, operation_name: &str) -> Result<()> {
+        let conflict_info_list = self.get_conflict_info_list()?;
+        if !conflict_info_list.is_empty() {
+            let paths: Vec<&str> = conflict_info_list.iter().map(|c| c.path.as_str()).collect();
+            bail!("Can't continue the {} while the following files have conflicts: {}. Resolve them first.", operation_name, paths.join(", "));
+        }
+        Ok(())
+    }
+
+    // Only staged changes are committed for a step, so anything unstaged would be left behind.
+    fn bail_if_unstaged_changes(&self, operation_name: &str) -> Result<()> {
+        if self.has_unstaged_tracked_changes()? {
+            bail!("Can't continue the {} while there are unstaged changes. Stage or discard them first.", operation_name);
+        }
+        Ok(())
+    }
+
+    fn find_index_conflict(index: &Index, path: &str) -> Result<IndexConflict> {
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entries = [&conflict.ancestor, &conflict.our, &conflict.their];
+            if entries.iter().any(|e| e.as_ref().map_or(false, |entry| entry.path == path.as_bytes())) {
+                return Ok(conflict);
+            }
+        }
+        bail!("'{}' doesn't have a conflict, it may have already been resolved.", path);
+    }
+
+    fn get_conflict_blob_content(&self, entry_opt: &Option<IndexEntry>) -> Result<(Option<String>, bool)> {
+        let repo = self.borrow_repo()?;
+        match entry_opt {
+            Some(entry) => {
+                let blob = repo.find_blob(entry.id)?;
+                if blob.is_binary() {
+                    return Ok((None, true));
+                }
+                Ok((Some(String::from_utf8_lossy(blob.content()).into_owned()), false))
+            },
+            None => Ok((None, false)),
+        }
+    }
+
+    pub fn get_conflict_versions(&self, json_str: &str) -> Result<ConflictVersions> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path: &str = GitManager::get_string_from_serde_string(path_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let conflict = GitManager::find_index_conflict(&repo.index()?, path)?;
+
+        let (base, is_base_binary) = self.get_conflict_blob_content(&conflict.ancestor)?;
+        let (ours, is_ours_binary) = self.get_conflict_blob_content(&conflict.our)?;
+        let (theirs, is_theirs_binary) = self.get_conflict_blob_content(&conflict.their)?;
+        let is_binary = is_base_binary || is_ours_binary || is_theirs_binary;
+
+        // The working directory file contains the conflict markers left by the operation.
+        let mut merged = None;
+        if !is_binary {
+            let workdir = match repo.workdir() {
+                Some(p) => p,
+                None => bail!("Repository doesn't have a working directory."),
+            };
+            let mut file_path = workdir.to_path_buf();
+            file_path.push(path);
+            if file_path.is_file() {
+                merged = Some(String::from_utf8_lossy(&fs::read(file_path)?).into_owned());
+            }
+        }
+
+        Ok(ConflictVersions {
+            path: String::from(path),
+            is_binary,
+            base,
+            ours,
+            theirs,
+            merged,
+        })
+    }
+
+    pub fn git_resolve_conflict(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let path = match json_hm.get("path") {
+            Some(s) => s,
+            None => bail!("path not included in payload from front-end."),
+        };
+        let resolution = match json_hm.get("resolution") {
+            Some(s) => s,
+            None => bail!("resolution not included in payload from front-end."),
+        };
+
+        let repo = self.borrow_repo()?;
+        let mut index = repo.index()?;
+        let conflict = GitManager::find_index_conflict(&index, path)?;
+
+        let workdir = match repo.workdir() {
+            Some(p) => p,
+            None => bail!("Repository doesn't have a working directory."),
+        };
+        let mut file_path = workdir.to_path_buf();
+        file_path.push(path);
+
+        if resolution == "merged" {
+            let merged_content = match json_hm.get("merged_content") {
+                Some(s) => s,
+                None => bail!("merged_content not included in payload from front-end."),
+            };
+            fs::write(&file_path, merged_content)?;
+            // Adding the path removes the conflict entries from the index.
+            index.add_path(Path::new(path))?;
+            index.write()?;
+            return Ok(());
+        }
+
+        let chosen_entry_opt = match resolution.as_str() {
+            "ours" => conflict.our,
+            "theirs" => conflict.their,
+            "base" => conflict.ancestor,
+            _ => bail!("Resolution '{}' isn't valid. Choices are 'ours', 'theirs', 'base', or 'merged'", resolution),
+        };
+
+        // Removing the path also removes its conflict entries.
+        index.remove_path(Path::new(path))?;
+        match chosen_entry_opt {
+            Some(mut entry) => {
+                // Clear the stage bits so the entry goes in as a normal, resolved entry.
+                entry.flags &= !INDEX_ENTRY_STAGE_MASK;
+                index.add(&entry)?;
+                index.write()?;
+                let mut checkout_builder = CheckoutBuilder::new();
+                checkout_builder.force();
+                checkout_builder.path(path);
+                repo.checkout_index(Some(&mut index), Some(&mut checkout_builder))?;
+            },
+            None => {
+                // The chosen side deleted the file, so resolve by removing it.
+                if file_path.exists() {
+                    fs::remove_file(&file_path)?;
+                }
+                index.write()?;
+            },
+        };
+
+        Ok(())
+    }
+
+    // Untracked files don't get in the way of a rebase, so they're left out here.
+    fn has_unstaged_tracked_changes(&self) -> Result<bool> {
+        let repo = self.borrow_repo()?;
+        let diff = repo.diff_index_to_workdir(None, None)?;
+        Ok(diff.deltas().len() > 0)
+    }
+
+    fn has_staged_changes(&self) -> Result<bool> {
+        let diff = self.get_staged_changes()?;
+
+        if diff.stats()?.files_changed() > 0 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn get_merge_source_description(&self, oid: Oid) -> Result<String> {
+        let repo = self.borrow_repo()?;
+
+        // Describe the merged commit the same way git does, preferring local branches, then remote branches, then tags.
+        let mut remote_branch_opt = None;
+        let mut tag_opt = None;
+        for reference in repo.references()? {
+            let reference = reference?;
+            let ref_name = match reference.name() {
+                Some(n) => n,
+                None => continue,
+            };
+            if ref_name.ends_with("/HEAD") || reference.peel_to_commit().map(|c| c.id()).ok() != Some(oid) {
+                continue;
+            }
+            if let Some(branch_name) = ref_name.strip_prefix("refs/heads/") {
+                return Ok(format!("branch '{}'", branch_name));
+            } else if let Some(remote_branch_name) = ref_name.strip_prefix("refs/remotes/") {
+                if remote_branch_opt.is_none() {
+                    remote_branch_opt = Some(format!("remote-tracking branch '{}'", remote_branch_name));
+                }
+            } else if let Some(tag_name) = ref_name.strip_prefix("refs/tags/") {
+                if tag_opt.is_none() {
+                    tag_opt = Some(format!("tag '{}'", tag_name));
+                }
+            }
+        }
+
+        match remote_branch_opt.or(tag_opt) {
+            Some(d) => Ok(d),
+            None => Ok(format!("commit '{}'", oid)),
+        }
+    }
+
+    fn get_default_merge_message(&self, oid: Oid) -> Result<String> {
+        let repo = self.borrow_repo()?;
+        let mut message = String::from("Merge ") + &*self.get_merge_source_description(oid)?;
+        let head_ref = repo.head()?;
+        if head_ref.is_branch() {
+            message += " into ";
+            message += GitManager::get_utf8_string(head_ref.shorthand(), "Branch Name")?;
+        }
+        Ok(message)
+    }
+
+    fn get_merge_msg_path(repo: &Repository) -> PathBuf {
+        let mut merge_msg_path = repo.path().to_path_buf();
+        merge_msg_path.push("MERGE_MSG");
+        merge_msg_path
+    }
+
+    fn get_squash_msg_path(repo: &Repository) -> PathBuf {
+        let mut squash_msg_path = repo.path().to_path_buf();
+        squash_msg_path.push("SQUASH_MSG");
+        squash_msg_path
+    }
+
+    // Left by a squash merge, so the front-end can fill in the commit message with it like git commit does.
+    pub fn get_squash_message(&self) -> Result<Option<String>> {
+        let repo = self.borrow_repo()?;
+        let squash_msg_path = GitManager::get_squash_msg_path(repo);
+        if !squash_msg_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(message_prettify(fs::read_to_string(squash_msg_path)?, Some(b'#'))?))
+    }
+
+    fn git_fast_forward_head(&self, target_oid: Oid, log_message: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let commit = repo.find_commit(target_oid)?;
+        repo.checkout_tree(commit.as_object(), None)?;
+
+        let mut head_ref = repo.head()?;
+        if head_ref.is_branch() {
+            head_ref.set_target(target_oid, log_message)?;
+        } else {
+            repo.set_head_detached(target_oid)?;
+        }
+
+        Ok(())
+    }
+
+    fn git_commit_merge(&self, message: &str, parent_commits: Vec<&Commit>) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        // Unlike git_commit, a merge commit is allowed to have no changes compared to HEAD.
+        let mut index = repo.index()?;
+        let tree_oid = index.write_tree()?;
+        index.write()?;
+        let tree = repo.find_tree(tree_oid)?;
+
+        let committer = self.get_signature()?;
+        let expected_head_opt = parent_commits.first().map(|c| c.id());
+        self.create_commit(&committer, &committer, message, &tree, parent_commits.as_slice(), expected_head_opt)?;
+
+        repo.cleanup_state()?;
+
+        Ok(())
+    }
+
+    fn write_squash_message(&self, head_oid: Oid, merge_oid: Oid) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(merge_oid)?;
+        revwalk.hide(head_oid)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+
+        let mut squash_message = String::from("Squashed commit of the following:\n");
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let author = commit.author();
+            squash_message += &*format!("\ncommit {}\nAuthor: {} <{}>\n\n", commit.id(), GitManager::get_utf8_string(author.name(), "Author Name")?, GitManager::get_utf8_string(author.email(), "Author Email")?);
+            for line in GitManager::get_utf8_string(commit.message(), "Commit Message")?.lines() {
+                squash_message += "    ";
+                squash_message += line;
+                squash_message += "\n";
+            }
+        }
+
+        fs::write(GitManager::get_squash_msg_path(repo), squash_message)?;
+
+        Ok(())
+    }
+
+    pub fn git_merge(&self, json_str: &str) -> Result<()> {
+        // The payload can either be just the sha to merge or include the merge mode and message too.
+        let json_value: Value = serde_json::from_str(json_str)?;
+        let json_hm: HashMap<String, String> = match json_value.as_str() {
+            Some(sha) => HashMap::from([(String::from("sha"), String::from(sha))]),
+            None => serde_json::from_value(json_value)?,
+        };
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha not included in payload from front-end."),
+        };
+        self.bail_if_interactive_rebasing("merge")?;
+
+        let repo = self.borrow_repo()?;
+        let merge_oid = Oid::from_str(sha)?;
+        let annotated_commit = repo.find_annotated_commit(merge_oid)?;
+        let (merge_analysis, merge_preference) = repo.merge_analysis(&[&annotated_commit])?;
+
+        // Fall back to the merge.ff git config (reflected in the merge preference) when no mode is chosen.
+        let merge_mode = match json_hm.get("merge_mode") {
+            Some(s) if s != "" => s.clone(),
+            _ => {
+                if merge_preference.is_no_fast_forward() {
+                    String::from("no-ff")
+                } else if merge_preference.is_fastforward_only() {
+                    String::from("ff-only")
+                } else {
+                    String::from("ff")
+                }
+            },
+        };
+        if !["ff", "no-ff", "ff-only", "squash"].contains(&&*merge_mode) {
+            bail!("Merge mode '{}' isn't valid. Choices are 'ff', 'no-ff', 'ff-only', or 'squash'", merge_mode);
+        }
+
+        if merge_analysis.is_unborn() {
+            bail!("The HEAD of the current repository is “unborn” and does not point to a valid commit. Nothing can be merged into it.");
+        } else if merge_analysis.is_up_to_date() {
+            bail!("Already up to date, there's nothing to merge.");
+        }
+
+        if merge_analysis.is_fast_forward() && (merge_mode == "ff" || merge_mode == "ff-only") {
+            return self.git_fast_forward_head(merge_oid, &*format!("oxidized_git merge: Fast-forward to {}", sha));
+        } else if merge_mode == "ff-only" {
+            bail!("Can't fast-forward, the branches have diverged. Choose a different merge mode to merge them.");
+        }
+
+        let message = match json_hm.get("message") {
+            Some(s) if s != "" => s.clone(),
+            _ => self.get_default_merge_message(merge_oid)?,
+        };
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD has no target, failed to merge. It should fail earlier than this since there'd be no HEAD to merge into."),
+        };
+
+        repo.merge(&[&annotated_commit], None, None)?;
+
+        if merge_mode == "squash" {
+            // Like git, a squash merge only updates the index and working directory, leaving the commit to the user.
+            repo.cleanup_state()?;
+            return self.write_squash_message(head_commit.id(), merge_oid);
+        }
+
+        // Overwrite the message prepared by libgit2 so continuing the merge uses the chosen message.
+        fs::write(GitManager::get_merge_msg_path(repo), &*message)?;
+
+        if !self.has_conflicts()? {
+            let merge_parent_two = repo.find_commit(merge_oid)?;
+            self.git_commit_merge(&*message, vec![&head_commit, &merge_parent_two])?;
+        }
+
+        Ok(())
+    }
+
+    // Returns false if the rebase stopped before the end and is left in progress.
+    fn iterate_through_rebase(&self, rebase: &mut Rebase) -> Result<bool> {
+        // Unfortunately, using 'rebase' like an iterator doesn't allow us to commit since
+        // rebase has to be borrowed mutably to commit.
+        let mut reached_end = false;
+        while !reached_end {
+            let step = rebase.next();
+            match step {
+                Some(r) => {
+                    r?;
+
+                    // If there are conflicts, need to let the user fix them before resuming
+                    // inside "git_continue_rebase"
+                    if self.has_conflicts()? {
+                        return Ok(false);
+                    }
+                    self.bail_if_unstaged_changes("rebase")?;
+                    if self.has_staged_changes()? {
+                        rebase.commit(None, &self.get_signature()?, None)?;
+                    }
+                },
+                None => reached_end = true,
+            };
+        }
+        rebase.finish(None)?;
+
+        Ok(true)
+    }
+
+    pub fn git_rebase(&self, json_str: &str) -> Result<()> {
+        let sha_value: Value = serde_json::from_str(json_str)?;
+        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+        let repo = self.borrow_repo()?;
+        let annotated_commit = repo.find_annotated_commit(Oid::from_str(sha)?)?;
+        let mut rebase = repo.rebase(None, None, Some(&annotated_commit), None)?;
+
+        self.iterate_through_rebase(&mut rebase)?;
+
+        Ok(())
+    }
+
+    pub fn is_interactive_rebasing(&self) -> Result<bool> {
+        let repo = self.borrow_repo()?;
+        Ok(InteractiveRebaseState::get_path(repo).exists())
+    }
+
+    pub fn get_rebase_todo_list(&self, json_str: &str) -> Result<Vec<RebaseTodoItem>> {
+        let sha_value: Value = serde_json::from_str(json_str)?;
+        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.hide(Oid::from_str(sha)?)?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+        let mut todo_list = vec![];
+        for oid_result in revwalk {
+            let commit = repo.find_commit(oid_result?)?;
+            // Like git, merge commits are left out of the todo list.
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            todo_list.push(RebaseTodoItem {
+                action: String::from("pick"),
+                sha: commit.id().to_string(),
+                summary: html_escape::encode_text(GitManager::get_utf8_string(commit.summary(), "Commit Summary")?).parse()?,
+                message: None,
+            });
+        }
+
+        Ok(todo_list)
+    }
+
+    fn has_uncommitted_changes(&self) -> Result<bool> {
+        let repo = self.borrow_repo()?;
+
+        let mut status_options = StatusOptions::new();
+        status_options.include_untracked(false);
+        status_options.include_ignored(false);
+        Ok(!repo.statuses(Some(&mut status_options))?.is_empty())
+    }
+
+    pub fn git_interactive_rebase(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let rebase_info: InteractiveRebaseInfo = serde_json::from_str(json_str)?;
+
+        if repo.state() != RepositoryState::Clean || self.is_interactive_rebasing()? {
+            bail!("Another operation is already in progress. Finish or abort it before starting a rebase.");
+        }
+        if self.has_uncommitted_changes()? {
+            bail!("You have uncommitted changes. Commit or stash them before starting a rebase.");
+        }
+
+        let mut has_picked_commit = false;
+        for item in &rebase_info.todo_list {
+            if !REBASE_ACTIONS.contains(&item.action.as_str()) {
+                bail!("'{}' isn't a valid rebase action. Choices are 'pick', 'reword', 'edit', 'squash', 'fixup', or 'drop'", item.action);
+            }
+            if (item.action == "squash" || item.action == "fixup") && !has_picked_commit {
+                bail!("Can't {} without a previous commit to combine it with.", item.action);
+            }
+            if item.action == "reword" && item.message.is_none() {
+                bail!("A new message is required to reword commit {}.", item.sha);
+            }
+            if repo.find_commit(Oid::from_str(&*item.sha)?)?.parent_count() > 1 && item.action != "drop" {
+                bail!("Commit {} is a merge commit, which can't be rebased interactively.", item.sha);
+            }
+            if item.action != "drop" {
+                has_picked_commit = true;
+            }
+        }
+
+        let head_ref = repo.head()?;
+        let head_name = if repo.head_detached()? {
+            None
+        } else {
+            Some(String::from(GitManager::get_utf8_string(head_ref.name(), "Branch Name")?))
+        };
+        let orig_head_sha = match head_ref.target() {
+            Some(oid) => oid.to_string(),
+            None => bail!("HEAD has no target commit, can't start a rebase."),
+        };
+
+        // Replay the todo list on a detached HEAD, the branch is only moved once the rebase is finished.
+        let onto_commit = repo.find_commit(Oid::from_str(&*rebase_info.onto_sha)?)?;
+        repo.checkout_tree(onto_commit.as_object(), None)?;
+        repo.set_head_detached(onto_commit.id())?;
+
+        let mut state = InteractiveRebaseState {
+            head_name,
+            orig_head_sha,
+            onto_sha: rebase_info.onto_sha,
+            todo_list: rebase_info.todo_list,
+            next_step: 0,
+            is_step_pending: false,
+            squash_target_sha: None,
+        };
+        state.save(repo)?;
+
+        self.iterate_through_interactive_rebase(&mut state)
+    }
+
+    fn commit_interactive_rebase_step(&self, state: &mut InteractiveRebaseState, step: &RebaseTodoItem) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let commit = repo.find_commit(Oid::from_str(&*step.sha)?)?;
+        let committer = self.get_signature()?;
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD has no target commit, can't continue rebase."),
+        };
+        let commit_message = GitManager::get_utf8_string(commit.message(), "Commit Message")?;
+
+        // Only combine with a commit from this rebase, never with the commit it started on.
+        let is_combining = (step.action == "squash" || step.action == "fixup")
+            && state.squash_target_sha.as_deref() == Some(&*head_commit.id().to_string());
+        if is_combining {
+            let head_message = GitManager::get_utf8_string(head_commit.message(), "Commit Message")?;
+            let new_message = if step.action == "fixup" {
+                String::from(head_message)
+            } else if let Some(m) = &step.message {
+                m.clone()
+            } else {
+                String::from(head_message.trim_end()) + "\n\n" + commit_message
+            };
+
+            self.git_amend_head(None, &committer, Some(&*new_message))?;
+        } else if self.has_staged_changes()? || step.action == "reword" {
+            // A reworded commit is kept even when it ends up empty, so the new message isn't lost.
+            let new_message = match &step.message {
+                Some(m) if step.action == "reword" || step.action == "squash" => m.clone(),
+                _ => String::from(commit_message),
+            };
+            let mut index = repo.index()?;
+            let tree = repo.find_tree(index.write_tree()?)?;
+            self.create_commit(&commit.author(), &committer, &*new_message, &tree, &[&head_commit], Some(head_commit.id()))?;
+        } else {
+            // Nothing is staged, so the changes are already present upstream and the commit is dropped.
+            InteractiveRebaseState::remove_cherrypick_state(repo, true)?;
+            return Ok(());
+        }
+
+        state.squash_target_sha = repo.head()?.target().map(|oid| oid.to_string());
+        InteractiveRebaseState::remove_cherrypick_state(repo, true)?;
+        Ok(())
+    }
+
+    fn iterate_through_interactive_rebase(&self, state: &mut InteractiveRebaseState) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        while state.next_step < state.todo_list.len() {
+            let step = state.todo_list[state.next_step].clone();
+            if step.action != "drop" {
+                let commit = repo.find_commit(Oid::from_str(&*step.sha)?)?;
+                let head_oid = match repo.head()?.target() {
+                    Some(oid) => oid,
+                    None => bail!("HEAD has no target commit, can't continue rebase."),
+                };
+
+                let is_unchanged_pick = step.action == "pick" || step.action == "edit";
+                if is_unchanged_pick && commit.parent_count() == 1 && commit.parent_id(0)? == head_oid {
+                    // The commit already sits on top of HEAD, so just move to it instead of recreating it.
+                    repo.checkout_tree(commit.as_object(), None)?;
+                    repo.set_head_detached(commit.id())?;
+                    state.squash_target_sha = Some(commit.id().to_string());
+                } else {
+                    repo.cherrypick(&commit, None)?;
+                    // The rebase directory tracks the operation, so don't leave the repo in a cherrypick state.
+                    InteractiveRebaseState::remove_cherrypick_state(repo, false)?;
+
+                    // If there are conflicts, need to let the user fix them before resuming
+                    // inside "git_continue_rebase"
+                    if self.has_conflicts()? {
+                        state.is_step_pending = true;
+                        state.save(repo)?;
+                        return Ok(());
+                    }
+                    self.commit_interactive_rebase_step(state, &step)?;
+                }
+            }
+
+            state.next_step += 1;
+            state.is_step_pending = false;
+            state.save(repo)?;
+            if step.action == "edit" {
+                return Ok(());
+            }
+        }
+
+        self.finish_interactive_rebase(state)
+    }
+
+    fn finish_interactive_rebase(&self, state: &InteractiveRebaseState) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        if let Some(head_name) = &state.head_name {
+            let new_target = match repo.head()?.target() {
+                Some(oid) => oid,
+                None => bail!("HEAD has no target commit, can't finish rebase."),
+            };
+            let mut branch_ref = repo.find_reference(head_name)?;
+            branch_ref.set_target(new_target, "oxidized_git rebase: finished interactive rebase")?;
+            repo.set_head(head_name)?;
+        }
+
+        InteractiveRebaseState::delete(repo)?;
+        Ok(())
+    }
+
+    fn git_abort_interactive_rebase(&self, state: &InteractiveRebaseState) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let orig_head_commit = repo.find_commit(Oid::from_str(&*state.orig_head_sha)?)?;
+        repo.reset(orig_head_commit.as_object(), ResetType::Hard, None)?;
+        match &state.head_name {
+            Some(head_name) => repo.set_head(head_name)?,
+            None => repo.set_head_detached(orig_head_commit.id())?,
+        };
+
+        repo.cleanup_state()?;
+        InteractiveRebaseState::delete(repo)?;
+        Ok(())
+    }
+
+    fn git_continue_interactive_rebase(&self, state: &mut InteractiveRebaseState) -> Result<()> {
+        // Need to make sure there are no conflicts before continuing.
+        self.bail_if_conflicted("rebase")?;
+
+        let repo = self.borrow_repo()?;
+        if state.is_step_pending {
+            let step = state.todo_list[state.next_step].clone();
+            self.commit_interactive_rebase_step(state, &step)?;
+            state.next_step += 1;
+            state.is_step_pending = false;
+            state.save(repo)?;
+            if step.action == "edit" {
+                return Ok(());
+            }
+        } else if self.has_staged_changes()? {
+            // Stopped at an "edit" step, so anything staged is folded into the commit being edited.
+            self.git_amend_head(None, &self.get_signature()?, None)?;
+            state.squash_target_sha = repo.head()?.target().map(|oid| oid.to_string());
+            state.save(repo)?;
+        }
+
+        self.iterate_through_interactive_rebase(state)
+    }
+
+    pub fn git_cherrypick(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha wasn't included in payload from the front-end."),
+        };
+        let is_committing = match json_hm.get("isCommitting") {
+            Some(s) => s == "true",
+            None => bail!("isCommitting wasn't included in payload from the front-end."),
+        };
+        self.bail_if_interactive_rebasing("cherrypick")?;
+
+        let repo = self.borrow_repo()?;
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+
+        repo.cherrypick(&commit, None)?;
+
+        if !self.has_conflicts()? {
+            repo.cleanup_state()?;
+        }
+
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+
+        if is_committing && !self.has_conflicts()? && self.has_staged_changes()? {
+            let committer = self.get_signature()?;
+            let head_commit = match repo.head()?.target() {
+                Some(oid) => repo.find_commit(oid)?,
+                None => bail!("HEAD has no target, failed to commit after cherrypick."),
+            };
+            self.git_commit(String::from(GitManager::get_utf8_string(commit.message(), "Commit Message")?), &commit.author(), &committer, vec![&head_commit])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn git_revert(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha wasn't included in payload from the front-end."),
+        };
+        let is_committing = match json_hm.get("isCommitting") {
+            Some(s) => s == "true",
+            None => bail!("isCommitting wasn't included in payload from the front-end."),
+        };
+        self.bail_if_interactive_rebasing("revert")?;
+
+        let repo = self.borrow_repo()?;
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+
+        repo.revert(&commit, None)?;
+
+        if !self.has_conflicts()? {
+            repo.cleanup_state()?;
+        }
+
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+
+        if is_committing && !self.has_conflicts()? && self.has_staged_changes()? {
+            let committer = self.get_signature()?;
+            let head_commit = match repo.head()?.target() {
+                Some(oid) => repo.find_commit(oid)?,
+                None => bail!("HEAD has no target, failed to commit after revert."),
+            };
+
+            let mut new_full_message = String::from("Revert \"") + GitManager::get_utf8_string(commit.summary(), "Commit Summary")? + "\"";
+            let (message_without_summary, uses_crlf) = GitManager::get_message_without_summary(GitManager::get_utf8_string(commit.message(), "Commit Message")?);
+            if uses_crlf {
+                new_full_message += "\r\n\r\n";
+            } else {
+                new_full_message += "\n\n";
+            }
+            new_full_message += message_without_summary.as_str();
+
+            self.git_commit(new_full_message, &commit.author(), &committer, vec![&head_commit])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn git_abort(&self) -> Result<()> {
+        self.bail_if_interactive_rebasing("abort")?;
+        let repo = self.borrow_repo()?;
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD doesn't have a target commit, cannot abort to HEAD"),
+        };
+
+        repo.reset(head_commit.as_object(), ResetType::Hard, None)?;
+
+        repo.cleanup_state()?;
+
+        Ok(())
+    }
+
+    pub fn git_continue_cherrypick(&self) -> Result<()> {
+        self.bail_if_conflicted("cherrypick")?;
+
+        let repo = self.borrow_repo()?;
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD doesn't have a target commit (which is where a cherrypick operation starts on), can't complete cherrypick."),
+        };
+
+        let mut cherrypick_head_file = repo.path().to_path_buf();
+        cherrypick_head_file.push("CHERRY_PICK_HEAD");
+        let cherrypick_head_string = fs::read_to_string(cherrypick_head_file)?;
+        let sha = cherrypick_head_string.trim();
+
+        let commit_from_op = repo.find_commit(Oid::from_str(sha)?)?;
+
+        let committer = self.get_signature()?;
+
+        self.git_commit(String::from(GitManager::get_utf8_string(commit_from_op.message(), "Commit Message")?), &commit_from_op.author(), &committer, vec![&head_commit])?;
+
+        repo.cleanup_state()?;
+
+        Ok(())
+    }
+
+    pub fn git_continue_revert(&self) -> Result<()> {
+        self.bail_if_conflicted("revert")?;
+
+        let repo = self.borrow_repo()?;
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD doesn't have a target commit (which is where a revert operation starts on), can't complete revert."),
+        };
+
+        let mut revert_file = repo.path().to_path_buf();
+        revert_file.push("REVERT_HEAD");
+        let revert_head_string = fs::read_to_string(revert_file)?;
+        let sha = revert_head_string.trim();
+
+        let commit_from_op = repo.find_commit(Oid::from_str(sha)?)?;
+
+        let committer = self.get_signature()?;
+
+        let mut new_full_message = String::from("Revert \"") + GitManager::get_utf8_string(commit_from_op.summary(), "Commit Summary")? + "\"";
+        let (message_without_summary, uses_crlf) = GitManager::get_message_without_summary(GitManager::get_utf8_string(commit_from_op.message(), "Commit Message")?);
+        if uses_crlf {
+            new_full_message += "\r\n\r\n";
+        } else {
+            new_full_message += "\n\n";
+        }
+        new_full_message += message_without_summary.as_str();
+
+        self.git_commit(new_full_message, &commit_from_op.author(), &committer, vec![&head_commit])?;
+
+        repo.cleanup_state()?;
+
+        Ok(())
+    }
+
+    pub fn git_continue_merge(&self) -> Result<()> {
+        self.bail_if_conflicted("merge")?;
+
+        let repo = self.borrow_repo()?;
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD doesn't have a target commit (which is where a merge operation starts on), can't complete merge."),
+        };
+
+        let mut merge_file = repo.path().to_path_buf();
+        merge_file.push("MERGE_HEAD");
+        let merge_head_string = fs::read_to_string(merge_file)?;
+        let mut merge_commits = vec![];
+        for sha in merge_head_string.lines() {
+            merge_commits.push(repo.find_commit(Oid::from_str(sha.trim())?)?);
+        }
+        let first_merge_commit = match merge_commits.first() {
+            Some(c) => c,
+            None => bail!("MERGE_HEAD doesn't contain any commits, can't complete merge."),
+        };
+
+        // Use the message prepared when the merge started, without any comment lines (e.g. the list of conflicts).
+        let merge_msg_path = GitManager::get_merge_msg_path(repo);
+        let mut message = String::new();
+        if merge_msg_path.exists() {
+            message = message_prettify(fs::read_to_string(merge_msg_path)?, Some(b'#'))?;
+        }
+        if message.trim().is_empty() {
+            message = self.get_default_merge_message(first_merge_commit.id())?;
+        }
+
+        let mut parent_commits = vec![&head_commit];
+        parent_commits.extend(merge_commits.iter());
+        self.git_commit_merge(&*message, parent_commits)?;
+
+        Ok(())
+    }
+
+    pub fn git_abort_rebase(&self) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        if let Some(state) = InteractiveRebaseState::load(repo)? {
+            return self.git_abort_interactive_rebase(&state);
+        }
+        let mut rebase = repo.open_rebase(None)?;
+
+        rebase.abort()?;
+        Ok(())
+    }
+
+    pub fn git_continue_rebase(&self) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        if let Some(mut state) = InteractiveRebaseState::load(repo)? {
+            return self.git_continue_interactive_rebase(&mut state);
+        }
+        let mut rebase = repo.open_rebase(None)?;
+
+        // Need to make sure there are no conflicts and we've committed before continuing.
+        self.bail_if_conflicted("rebase")?;
+        self.bail_if_unstaged_changes("rebase")?;
+        if self.has_staged_changes()? {
+            rebase.commit(None, &self.get_signature()?, None)?;
+        }
+
+        self.iterate_through_rebase(&mut rebase)?;
+
+        Ok(())
+    }
+
+    pub fn git_reset(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha wasn't included in payload from the front-end."),
+        };
+        let reset_type_string = match json_hm.get("type") {
+            Some(s) => s,
+            None => bail!("type wasn't included in payload from the front-end."),
+        };
+
+        let reset_type;
+        if reset_type_string == "soft" {
+            reset_type = ResetType::Soft;
+        } else if reset_type_string == "mixed" {
+            reset_type = ResetType::Mixed;
+        } else if reset_type_string == "hard" {
+            reset_type = ResetType::Hard;
+        } else {
+            bail!("type from front-end payload isn't a valid option. Choices are 'soft', 'mixed', or 'hard'");
+        }
+
+        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+
+        repo.reset(commit.as_object(), reset_type, None)?;
+
+        Ok(())
+    }
+
+    pub fn git_add_remote(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let remote_name = match json_hm.get("remote_name") {
+            Some(s) => s,
+            None => bail!("remote_name not included in payload from the front-end"),
+        };
+        let remote_url = match json_hm.get("remote_url") {
+            Some(s) => s,
+            None => bail!("remote_url not included in payload from the front-end"),
+        };
+
+        repo.remote(remote_name.as_str(), remote_url.as_str())?;
+
+        Ok(())
+    }
+
+    fn get_string_array_vec(string_array: StringArray, string_name: &str) -> Result<Vec<String>> {
+        let mut strings = vec![];
+        for s_opt in string_array.iter() {
+            strings.push(String::from(GitManager::get_utf8_string(s_opt, string_name)?));
+        }
+        Ok(strings)
+    }
+
+    pub fn get_remotes(&self) -> Result<Vec<RemoteInfo>> {
+        let repo = self.borrow_repo()?;
+
+        let mut remote_infos = vec![];
+        for remote_name_opt in repo.remotes()?.iter() {
+            let remote_name = GitManager::get_utf8_string(remote_name_opt, "Remote Name")?;
+            let remote = repo.find_remote(remote_name)?;
+            remote_infos.push(RemoteInfo {
+                name: String::from(remote_name),
+                url: remote.url().map(String::from),
+                push_url: remote.pushurl().map(String::from),
+                fetch_refspecs: GitManager::get_string_array_vec(remote.fetch_refspecs()?, "Refspec")?,
+                push_refspecs: GitManager::get_string_array_vec(remote.push_refspecs()?, "Refspec")?,
+            });
+        }
+        Ok(remote_infos)
+    }
+
+    pub fn git_rename_remote(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let remote_name = match json_hm.get("remote_name") {
+            Some(s) => s,
+            None => bail!("remote_name not included in payload from front-end."),
+        };
+        let new_remote_name = match json_hm.get("new_remote_name") {
+            Some(s) => s,
+            None => bail!("new_remote_name not included in payload from front-end."),
+        };
+
+        let repo = self.borrow_repo()?;
+        // This also moves the remote-tracking branches and the branch.<name>.remote entries of tracking branches.
+        let problems = repo.remote_rename(remote_name, new_remote_name)?;
+
+        // libgit2 leaves branch.<name>.pushRemote alone, so point those at the new name too.
+        let mut config = repo.config()?;
+        let mut push_remote_keys = vec![];
+        {
+            let mut entries = config.entries(Some("branch\\..*\\.pushremote"))?;
+            while let Some(entry_result) = entries.next() {
+                let entry = entry_result?;
+                if entry.value() == Some(remote_name.as_str()) {
+                    push_remote_keys.push(String::from(GitManager::get_utf8_string(entry.name(), "Config Key")?));
+                }
+            }
+        }
+        for push_remote_key in push_remote_keys {
+            config.set_str(&push_remote_key, new_remote_name)?;
+        }
+
+        if !problems.is_empty() {
+            let problem_refspecs = GitManager::get_string_array_vec(problems, "Refspec")?;
+            bail!("Remote renamed, but these non-default fetch refspecs couldn't be updated automatically and need to be edited by hand: {}", problem_refspecs.join(", "));
+        }
+
+        Ok(())
+    }
+
+    pub fn git_remove_remote(&self, json_str: &str) -> Result<()> {
+        let remote_name_value: Value = serde_json::from_str(json_str)?;
+        let remote_name: &str = GitManager::get_string_from_serde_string(remote_name_value.as_str())?;
+        let repo = self.borrow_repo()?;
+        let remote = repo.find_remote(remote_name)?;
+
+        // libgit2 fails to delete a remote with more than one fetch or push refspec, so the remote-tracking
+        // refs are removed here and the refspecs cleared out before deleting it. Like git, refs outside of
+        // refs/remotes (e.g. tags from a tag refspec) are kept.
+        let remote_head_name = format!("refs/remotes/{}/HEAD", remote_name);
+        let mut tracking_refs = vec![];
+        for reference_result in repo.references()? {
+            let reference = reference_result?;
+            let reference_name = GitManager::get_utf8_string(reference.name(), "Reference Name")?;
+            if !reference_name.starts_with("refs/remotes/") {
+                continue;
+            }
+            if reference_name == remote_head_name || remote.refspecs().any(|r| r.direction() == Direction::Fetch && r.dst_matches(reference_name)) {
+                tracking_refs.push(reference);
+            }
+        }
+        for mut tracking_ref in tracking_refs {
+            tracking_ref.delete()?;
+        }
+
+        let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+        for refspec_key in [format!("remote.{}.fetch", remote_name), format!("remote.{}.push", remote_name)] {
+            match config.remove_multivar(&refspec_key, ".*") {
+                Ok(()) => (),
+                Err(e) => {
+                    if e.code() != ErrorCode::NotFound {
+                        return Err(e.into());
+                    }
+                },
+            };
+        }
+
+        let mut push_remote_keys = vec![];
+        {
+            let mut entries = config.entries(Some("branch\\..*\\.pushremote"))?;
+            while let Some(entry_result) = entries.next() {
+                let entry = entry_result?;
+                if entry.value() == Some(remote_name) {
+                    push_remote_keys.push(String::from(GitManager::get_utf8_string(entry.name(), "Config Key")?));
+                }
+            }
+        }
+        for push_remote_key in push_remote_keys {
+            config.remove(&push_remote_key)?;
+        }
+
+        // This also unsets the upstream of branches tracking the remote.
+        repo.remote_delete(remote_name)?;
+        Ok(())
+    }
+
+    pub fn git_set_remote_url(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let remote_name = match json_hm.get("remote_name") {
+            Some(s) => s,
+            None => bail!("remote_name not included in payload from front-end."),
+        };
+        let remote_url = match json_hm.get("remote_url") {
+            Some(s) => s,
+            None => bail!("remote_url not included in payload from front-end."),
+        };
+        // An empty push URL means pushes use remote_url.
+        let push_url = match json_hm.get("push_url") {
+            Some(s) => s,
+            None => bail!("push_url not included in payload from front-end."),
+        };
+
+        let repo = self.borrow_repo()?;
+        repo.remote_set_url(remote_name, remote_url)?;
+        if push_url == "" {
+            if repo.find_remote(remote_name)?.pushurl().is_some() {
+                repo.remote_set_pushurl(remote_name, None)?;
+            }
+        } else {
+            repo.remote_set_pushurl(remote_name, Some(push_url))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn git_set_remote_fetch_refspecs(&self, json_str: &str) -> Result<()> {
+        let remote_fetch_refspecs: RemoteFetchRefspecs = serde_json::from_str(json_str)?;
+        let repo = self.borrow_repo()?;
+
+        // Make sure the remote exists before touching its config.
+        repo.find_remote(&remote_fetch_refspecs.remote_name)?;
+        for fetch_refspec in &remote_fetch_refspecs.fetch_refspecs {
+            if fetch_refspec.trim() == "" {
+                bail!("Fetch refspecs can't be empty.");
+            }
+        }
+
+        let fetch_key = format!("remote.{}.fetch", remote_fetch_refspecs.remote_name);
+        let mut config = repo.config()?.open_level(ConfigLevel::Local)?;
+        match config.remove_multivar(&fetch_key, ".*") {
+            Ok(()) => (),
+            Err(e) => {
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+            },
+        };
+        for fetch_refspec in &remote_fetch_refspecs.fetch_refspecs {
+            repo.remote_add_fetch(&remote_fetch_refspecs.remote_name, fetch_refspec.trim())?;
+        }
+
+        Ok(())
+    }
+
+    fn git_checkout(&self, local_ref: &Reference) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let local_full_name = GitManager::get_utf8_string(local_ref.name(), "Branch Name")?;
+        if let Some(worktree_path) = self.get_branch_worktree_paths()?.get(local_full_name) {
+            bail!("Branch '{}' is already checked out in the worktree at '{}'. Switch to that worktree or check out a different branch.", GitManager::get_utf8_string(local_ref.shorthand(), "Branch Name")?, worktree_path);
+        }
+        let commit = match local_ref.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("Trying to check out branch that has no target commit."),
+        };
+        let tree = commit.tree()?;
+
+        repo.checkout_tree(tree.as_object(), None)?;
+        repo.set_head(local_full_name)?;
+        Ok(())
+    }
+
+    pub fn git_checkout_from_json(&self, json_str: &str) -> Result<()> {
+        let ref_name_value: Value = serde_json::from_str(json_str)?;
+        let ref_name: &str = GitManager::get_string_from_serde_string(ref_name_value.as_str())?;
+        self.git_checkout(&self.borrow_repo()?.find_reference(ref_name)?)?;
+        Ok(())
+    }
+
+    pub fn git_checkout_detached_head(&self, json_str: &str) -> Result<()> {
+        let sha_value: Value = serde_json::from_str(json_str)?;
+        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let oid = Oid::from_str(sha)?;
+        let tree = repo.find_commit(oid)?.tree()?;
+
+        repo.checkout_tree(tree.as_object(), None)?;
+        repo.set_head_detached(oid)?;
+        Ok(())
+    }
+
+    pub fn git_checkout_remote(&self, json_string: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_string)?;
+        let remote_branch_shortname = match json_hm.get("branch_shorthand") {
+            Some(n) => n,
+            None => bail!("JSON Data is missing branch_shorthand attribute."),
+        };
+        let remote_branch_full_name = match json_hm.get("full_branch_name") {
+            Some(n) => n,
+            None => bail!("JSON Data is missing full_branch_name attribute."),
+        };
+
+        // Look for a local branch that already exists for the specified remote branch. If one exists,
+        // check it out instead.
+        for local_b_result in repo.branches(Some(BranchType::Local))? {
+            let (local_b, _) = local_b_result?;
+            let local_upstream = match local_b.upstream() {
+                Ok(b) => b,
+                Err(_) => {
+                    continue;
+                },
+            };
+            let local_upstream_full_name = GitManager::get_utf8_string(local_upstream.get().name(), "Branch Name")?;
+            if local_upstream_full_name == remote_branch_full_name {
+                return self.git_checkout(local_b.get());
+            }
+        }
+
+        // If there's no local branch, create a new one with the name used by the remote branch.
+        let remote_branch_name_parts: Vec<&str> = remote_branch_shortname.split("/").collect();
+        let mut local_branch_shortname = String::new();
+        for i in 1..remote_branch_name_parts.len() {
+            local_branch_shortname += remote_branch_name_parts[i];
+            if i < remote_branch_name_parts.len() - 1 {
+                local_branch_shortname += "/";
+            }
+        }
+        let remote_branch = repo.find_branch(remote_branch_shortname, BranchType::Remote)?;
+        let commit = match remote_branch.get().target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("Selected remote branch isn't targeting a commit, can't checkout!"),
+        };
+        let mut local_branch = repo.branch(&*local_branch_shortname, &commit, false)?;
+        local_branch.set_upstream(Some(remote_branch_shortname))?;
+
+        self.git_checkout(local_branch.get())
+    }
+
+    fn git_stage(&self, status: u8, path: &String) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let mut index = repo.index()?;
+        if status == 2 {  // If file is deleted
+            index.remove_path(path.as_ref())?;
+        } else {
+            index.add_path(path.as_ref())?;
+        }
+        index.write()?;
+
+        Ok(())
+    }
+
+    pub fn git_stage_from_json(&self, json_str: &str) -> Result<()> {
+        let diff_delta: ParseableDiffDelta = serde_json::from_str(json_str)?;
+
+        self.git_stage(diff_delta.get_status(), diff_delta.get_path())?;
+
+        Ok(())
+    }
+
+    pub fn git_unstage(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let diff_delta: ParseableDiffDelta = serde_json::from_str(json_str)?;
+
+        let mut index = repo.index()?;
+        let status = diff_delta.get_status();
+        if status == 2 || status == 3 {  // If file is deleted or modified
+            let head_commit = match repo.head()?.target() {
+                Some(oid) => {
+                    repo.find_commit(oid)?
+                },
+                None => bail!("Head has no target commit"),
+            };
+            repo.reset_default(Some(head_commit.as_object()), [diff_delta.get_path()])?;
+        } else {
+            index.remove_path(diff_delta.get_path().as_ref())?;
+        }
+        index.write()?;
+
+        Ok(())
+    }
+
+    fn build_partial_patch(patch: &Patch, partial_change: &PartialChangeInfo, is_reverse: bool) -> Result<Vec<u8>> {
+        let delta = patch.delta();
+        if delta.flags().is_binary() {
+            bail!("Binary files can't be partially staged or unstaged, stage or unstage the whole file instead.");
+        }
+        if delta.status() == Delta::Renamed || delta.status() == Delta::Copied {
+            bail!("Renamed or copied files can't be partially staged or unstaged, stage or unstage the whole file instead.");
+        }
+        for hunk_index in &partial_change.hunk_indexes {
+            if *hunk_index >= patch.num_hunks() {
+                bail!("Selected hunk no longer exists. The file may have changed since its diff was loaded, please refresh and try again.");
+            }
+        }
+
+        let mut matched_line_count = 0;
+        let mut has_unselected_changes = false;
+        let mut hunks_text: Vec<u8> = vec![];
+        // The difference between where a hunk starts in the file the patch is applied to and where it starts in the result.
+        let mut line_offset: i64 = 0;
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_index)?;
+            let mut hunk_text: Vec<u8> = vec![];
+            let mut hunk_has_changes = false;
+            let mut old_count: i64 = 0;
+            let mut new_count: i64 = 0;
+            for line_index in 0..line_count {
+                let diff_line = patch.line_in_hunk(hunk_index, line_index)?;
+                if partial_change.lines.iter().any(|l| l.matches_diff_line(&diff_line)) {
+                    matched_line_count += 1;
+                }
+
+                // Unstaging applies the staged diff in reverse, so additions and deletions swap.
+                let origin = match (diff_line.origin_value(), is_reverse) {
+                    (DiffLineType::Context, _) => ' ',
+                    (DiffLineType::Addition, false) | (DiffLineType::Deletion, true) => '+',
+                    (DiffLineType::Deletion, false) | (DiffLineType::Addition, true) => '-',
+                    // "No newline at end of file" markers are re-added below based on the line content.
+                    _ => continue,
+                };
+
+                let prefix;
+                if origin == ' ' {
+                    prefix = ' ';
+                } else if partial_change.is_line_selected(hunk_index, &diff_line) {
+                    hunk_has_changes = true;
+                    prefix = origin;
+                } else if origin == '-' {
+                    // An unselected removal stays in the file, so it becomes context.
+                    has_unselected_changes = true;
+                    prefix = ' ';
+                } else {
+                    // An unselected addition is simply left out.
+                    has_unselected_changes = true;
+                    continue;
+                }
+
+                if prefix != '+' {
+                    old_count += 1;
+                }
+                if prefix != '-' {
+                    new_count += 1;
+                }
+                hunk_text.push(prefix as u8);
+                hunk_text.extend_from_slice(diff_line.content());
+                if !diff_line.content().ends_with(b"\n") {
+                    hunk_text.extend_from_slice(b"\n\\ No newline at end of file\n");
+                }
+            }
+
+            if !hunk_has_changes {
+                continue;
+            }
+
+            let old_start = (if is_reverse { hunk.new_start() } else { hunk.old_start() }) as i64;
+            // Empty ranges in hunk headers refer to the line before the hunk rather than its first line.
+            let first_line = if old_count == 0 { old_start + 1 } else { old_start };
+            let mut new_start = first_line + line_offset;
+            if new_count == 0 {
+                new_start -= 1;
+            }
+            line_offset += new_count - old_count;
+
+            hunks_text.extend_from_slice(format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count).as_bytes());
+            hunks_text.extend_from_slice(&hunk_text);
+        }
+
+        if matched_line_count < partial_change.lines.len() {
+            bail!("Some of the selected lines no longer exist. The file may have changed since its diff was loaded, please refresh and try again.");
+        }
+        if hunks_text.is_empty() {
+            bail!("No changes were selected to stage or unstage.");
+        }
+
+        // Work out whether the file exists before and after the patch, from the point of view of the index.
+        let is_target_missing = (!is_reverse && (delta.status() == Delta::Added || delta.status() == Delta::Untracked)) || (is_reverse && delta.status() == Delta::Deleted);
+        let is_result_missing = !has_unselected_changes && ((!is_reverse && delta.status() == Delta::Deleted) || (is_reverse && delta.status() == Delta::Added));
+        let path = &partial_change.file_path;
+        let mut patch_text = format!("diff --git a/{path} b/{path}\n").into_bytes();
+        if is_target_missing {
+            let new_mode = if is_reverse { delta.old_file().mode() } else { delta.new_file().mode() };
+            patch_text.extend_from_slice(format!("new file mode {:o}\n--- /dev/null\n+++ b/{path}\n", u32::from(new_mode)).as_bytes());
+        } else if is_result_missing {
+            let old_mode = if is_reverse { delta.new_file().mode() } else { delta.old_file().mode() };
+            patch_text.extend_from_slice(format!("deleted file mode {:o}\n--- a/{path}\n+++ /dev/null\n", u32::from(old_mode)).as_bytes());
+        } else {
+            patch_text.extend_from_slice(format!("--- a/{path}\n+++ b/{path}\n").as_bytes());
+        }
+        patch_text.extend_from_slice(&hunks_text);
+
+        Ok(patch_text)
+    }
+
+    fn git_apply_partial_change(&self, json_str: &str, is_staging: bool) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let partial_change: PartialChangeInfo = serde_json::from_str(json_str)?;
+
+        let diff;
+        if is_staging {
+            diff = self.get_unstaged_changes()?;
+        } else {
+            diff = self.get_staged_changes()?;
+        }
+
+        let file_index = GitManager::get_file_index_in_diff(&diff, partial_change.file_path.as_str())?;
+        let patch = match Patch::from_diff(&diff, file_index)? {
+            Some(p) => p,
+            None => bail!("Patch not found in diff."),
+        };
+
+        // Staging applies the workdir changes onto the index, unstaging applies the staged changes in reverse.
+        let patch_text = GitManager::build_partial_patch(&patch, &partial_change, !is_staging)?;
+        let partial_diff = Diff::from_buffer(&patch_text)?;
+
+        if let Err(e) = repo.apply(&partial_diff, ApplyLocation::Index, None) {
+            if e.code() == ErrorCode::ApplyFail {
+                bail!("The selected changes conflict with the current index. It may have changed since the diff was loaded, please refresh and try again.");
+            }
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn git_stage_lines(&self, json_str: &str) -> Result<()> {
+        self.git_apply_partial_change(json_str, true)
+    }
+
+    pub fn git_unstage_lines(&self, json_str: &str) -> Result<()> {
+        self.git_apply_partial_change(json_str, false)
+    }
+
+    fn set_diff_find_similar(diff: &mut Diff) -> Result<()> {
+        let mut opts = DiffFindOptions::new();
+        opts.renames(true);
+        opts.copies(true);
+
+        diff.find_similar(Some(&mut opts))?;
+        Ok(())
+    }
+
+    pub fn get_unstaged_changes(&self) -> Result<Diff> {
+        let repo = self.borrow_repo()?;
+
+        let mut diff_options = DiffOptions::new();
+        diff_options.include_untracked(true);
+        diff_options.recurse_untracked_dirs(true);
+        diff_options.show_untracked_content(true);
+
+        let mut diff = repo.diff_index_to_workdir(None, Some(&mut diff_options))?;
+        GitManager::set_diff_find_similar(&mut diff)?;
+
+        Ok(diff)
+    }
+
+    pub fn get_staged_changes(&self) -> Result<Diff> {
+        let repo = self.borrow_repo()?;
+
+        let mut tree = None;
+        match repo.head() {
+            Ok(head_ref) => {
+                let commit = match head_ref.target() {
+                    Some(oid) => Some(repo.find_commit(oid)?),
+                    None => None,
+                };
+                tree = match commit {
+                    Some(c) => Some(c.tree()?),
+                    None => None,
+                };
+            },
+            Err(e) => {
+                if e.code() != ErrorCode::UnbornBranch {
+                    return Err(e.into());
+                }
+            },
+        };
+
+        let mut diff = repo.diff_tree_to_index(tree.as_ref(), None, None)?;
+        GitManager::set_diff_find_similar(&mut diff)?;
+
+        Ok(diff)
+    }
+
+    fn get_file_index_in_diff(diff: &Diff, path: &str) -> Result<usize> {
+        let file_index_opt = diff.deltas().position(|dd| {
+            match dd.new_file().path() {
+                Some(p) => {
+                    match p.to_str() {
+                        Some(s) => path == s,
+                        None => false,
+                    }
+                },
+                None => false,
+            }
+        });
+        match file_index_opt {
+            Some(i) => Ok(i),
+            None => bail!("Selected file not found."),
+        }
+    }
+
+    pub fn get_file_diff(&self, json_str: &str) -> Result<FileInfo> {
+        self.cancel_handle.start_step();
+        let repo = self.borrow_repo()?;
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let file_path = match json_hm.get("file_path") {
+            Some(s) => s,
+            None => bail!("file_path not returned from front-end payload."),
+        };
+        let change_type = match json_hm.get("change_type") {
+            Some(s) => s,
+            None => bail!("change_type not returned from front-end payload."),
+        };
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha not returned from front-end payload."),
+        };
+
+        let diff;
+        if change_type == "unstaged" {
+            diff = self.get_unstaged_changes()?;
+        } else if change_type == "staged" {
+            diff = self.get_staged_changes()?;
+        } else if change_type == "commit" {
+            let commit = repo.find_commit(Oid::from_str(sha)?)?;
+            diff = get_commit_changes(&commit, &repo)?;
+        } else if change_type == "stash" {
+            let stash_commit = repo.find_commit(Oid::from_str(sha)?)?;
+            diff = get_stash_diff(&stash_commit, &repo)?;
+        } else {
+            bail!("change_type not a valid type. Needs to be 'staged', 'unstaged', 'commit', or 'stash'");
+        }
+
+        let file_index = GitManager::get_file_index_in_diff(&diff, file_path.as_str())?;
+        GitManager::get_file_info_from_diff(&diff, file_index, file_path, change_type, &self.cancel_handle)
+    }
+
+    fn get_file_info_from_diff(diff: &Diff, file_index: usize, file_path: &str, change_type: &String, cancel_handle: &CancelHandle) -> Result<FileInfo> {
+        let patch_opt = Patch::from_diff(diff, file_index)?;
+        let mut file_lines = vec![];
+        let file_type = String::from(file_path.split(".").last().unwrap_or(""));
+        match patch_opt {
+            Some(mut patch) => {
+                patch.print(&mut |_diff_delta, _diff_hunk_opt, diff_line| {
+                    if diff_line.origin_value() == DiffLineType::FileHeader {
+                        if let Ok(s) = get_content_from_diff_line(&diff_line) {
+                            // Include file header if filemode has changed or the file was renamed.
+                            let is_filemode_change = !s.contains("+++") && (s.contains("old mode") || s.contains("new mode"));
+                            let is_renamed_file = s.contains("rename") || s.contains("similarity");
+                            if is_filemode_change || is_renamed_file {
+                                file_lines.push(LineInfo::SomeSeparator(s));
+                            }
+                        }
+                    } else if diff_line.origin_value() == DiffLineType::HunkHeader {
+                        if let Ok(s) = get_content_from_diff_line(&diff_line) {
+                            file_lines.push(LineInfo::SomeSeparator(s));
+                        }
+                    } else if let Ok(fli) = FileLineInfo::from_diff_line(diff_line, &file_type) {
+                        file_lines.push(LineInfo::SomeFileLineInfo(fli));
+                    }
+                    !cancel_handle.is_cancelled()
+                }).map_err(|e| cancel_handle.map_git_error(e))?;
+            },
+            None => bail!("Patch not found in diff."),
+        }
+
+        let file_info = FileInfo::new(change_type.clone(), file_lines);
+        Ok(file_info)
+    }
+
+    fn get_blob_oid_at_path(tree: &Tree, path: &str) -> Result<Option<Oid>> {
+        match tree.get_path(Path::new(path)) {
+            Ok(e) => Ok(Some(e.id())),
+            Err(e) => {
+                if e.code() == ErrorCode::NotFound {
+                    Ok(None)
+                } else {
+                    Err(e.into())
+                }
+            },
+        }
+    }
+
+    pub fn get_file_history(&self, json_str: &str) -> Result<Vec<FileHistoryEntry>> {
+        self.cancel_handle.start_step();
+        let repo = self.borrow_repo()?;
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let file_path = match json_hm.get("file_path") {
+            Some(s) => s,
+            None => bail!("file_path not included in payload from front-end."),
+        };
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha not included in payload from front-end."),
+        };
+
+        let mut revwalk = repo.revwalk()?;
+        if sha == "" {
+            revwalk.push_head()?;
+        } else {
+            revwalk.push(Oid::from_str(sha)?)?;
+        }
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        // Like "git log --follow", only one path is followed, which changes each time a rename is found.
+        let mut current_path = file_path.clone();
+        let mut file_history = vec![];
+        for (i, oid_result) in revwalk.enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 {
+                self.cancel_handle.check()?;
+            }
+            let commit = repo.find_commit(oid_result?)?;
+            let commit_tree = commit.tree()?;
+            let blob_oid_opt = GitManager::get_blob_oid_at_path(&commit_tree, &*current_path)?;
+
+            let mut parent_trees = vec![];
+            for parent in commit.parents() {
+                parent_trees.push(parent.tree()?);
+            }
+            let mut parent_blob_oid_opts = vec![];
+            for parent_tree in &parent_trees {
+                parent_blob_oid_opts.push(GitManager::get_blob_oid_at_path(parent_tree, &*current_path)?);
+            }
+
+            // Skip commits that didn't touch the file, including merges that took the file from one of their parents.
+            if blob_oid_opt.is_none() && parent_blob_oid_opts.iter().all(|o| o.is_none()) {
+                continue;
+            }
+            if parent_blob_oid_opts.iter().any(|o| *o == blob_oid_opt) {
+                continue;
+            }
+
+            let mut diff = repo.diff_tree_to_tree(parent_trees.first(), Some(&commit_tree), None)?;
+            GitManager::set_diff_find_similar(&mut diff)?;
+            let file_index_opt = diff.deltas().position(|dd| {
+                let delta_path = if dd.status() == Delta::Deleted { dd.old_file().path() } else { dd.new_file().path() };
+                delta_path.and_then(|p| p.to_str()) == Some(&*current_path)
+            });
+            let file_index = match file_index_opt {
+                Some(i) => i,
+                None => continue,
+            };
+
+            let delta = match diff.get_delta(file_index) {
+                Some(d) => d,
+                None => bail!("File not found in its commit's diff."),
+            };
+            let status = match delta.status() {
+                Delta::Added => "added",
+                Delta::Deleted => "deleted",
+                Delta::Renamed => "renamed",
+                Delta::Copied => "copied",
+                _ => "modified",
+            };
+            let old_path_opt = match delta.status() {
+                Delta::Renamed | Delta::Copied => Some(String::from(GitManager::get_utf8_string(delta.old_file().path().and_then(|p| p.to_str()), "Old File Path")?)),
+                _ => None,
+            };
+            let is_added = delta.status() == Delta::Added;
+
+            let author = commit.author();
+            file_history.push(FileHistoryEntry {
+                sha: commit.id().to_string(),
+                summary: html_escape::encode_text(GitManager::get_utf8_string(commit.summary(), "Commit Summary")?).parse()?,
+                author_name: html_escape::encode_text(GitManager::get_utf8_string(author.name(), "Author Name")?).parse()?,
+                author_time: author.when().seconds(),
+                path: current_path.clone(),
+                old_path: old_path_opt.clone(),
+                status: String::from(status),
+                file_info: GitManager::get_file_info_from_diff(&diff, file_index, &*current_path, &String::from("commit"), &self.cancel_handle)?,
+            });
+
+            if let Some(old_path) = old_path_opt {
+                current_path = old_path;
+            } else if is_added {
+                // The file started here, anything older at the same path is a different file.
+                break;
+            }
+        }
+
+        Ok(file_history)
+    }
+
+    pub fn get_blame(&self, json_str: &str) -> Result<BlameInfo> {
+        let repo = self.borrow_repo()?;
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+
+        let file_path = match json_hm.get("file_path") {
+            Some(s) => s,
+            None => bail!("file_path not included in payload from front-end."),
+        };
+        let sha = match json_hm.get("sha") {
+            Some(s) => s,
+            None => bail!("sha not included in payload from front-end."),
+        };
+        // Re-blaming from the parent shows who changed the lines before the given commit.
+        let from_parent = match json_hm.get("from_parent") {
+            Some(s) => s == "true",
+            None => bail!("from_parent not included in payload from front-end."),
+        };
+
+        let mut commit = if sha == "" {
+            match repo.head()?.target() {
+                Some(oid) => repo.find_commit(oid)?,
+                None => bail!("HEAD has no target commit to blame from."),
+            }
+        } else {
+            repo.find_commit(Oid::from_str(sha)?)?
+        };
+        if from_parent {
+            commit = match commit.parents().next() {
+                Some(c) => c,
+                None => bail!("Commit {} has no parent, so there's nothing earlier to blame.", commit.id()),
+            };
+        }
+
+        let file_entry = match commit.tree()?.get_path(Path::new(file_path)) {
+            Ok(e) => e,
+            Err(e) => {
+                if e.code() == ErrorCode::NotFound {
+                    bail!("'{}' doesn't exist at commit {}.", file_path, commit.id());
+                }
+                return Err(e.into());
+            },
+        };
+        let blob = repo.find_blob(file_entry.id())?;
+        if blob.is_binary() {
+            bail!("'{}' is a binary file and can't be blamed.", file_path);
+        }
+
+        let mut blame_options = BlameOptions::new();
+        blame_options.newest_commit(commit.id());
+        let blame = repo.blame_file(Path::new(file_path), Some(&mut blame_options))?;
+
+        // Many hunks usually come from the same commit, so only look each commit up once.
+        let mut commit_summaries: HashMap<Oid, String> = HashMap::new();
+        let mut hunks = vec![];
+        for hunk in blame.iter() {
+            let hunk_oid = hunk.final_commit_id();
+            let summary = match commit_summaries.get(&hunk_oid) {
+                Some(s) => s.clone(),
+                None => {
+                    let hunk_commit = repo.find_commit(hunk_oid)?;
+                    let summary: String = html_escape::encode_text(GitManager::get_utf8_string(hunk_commit.summary(), "Commit Summary")?).parse()?;
+                    commit_summaries.insert(hunk_oid, summary.clone());
+                    summary
+                },
+            };
+            let signature = hunk.final_signature();
+            let orig_path = match hunk.path() {
+                Some(p) => GitManager::get_utf8_string(p.to_str(), "Original File Path")?,
+                None => file_path.as_str(),
+            };
+            hunks.push(BlameHunkInfo {
+                sha: hunk_oid.to_string(),
+                author_name: html_escape::encode_text(GitManager::get_utf8_string(signature.name(), "Author Name")?).parse()?,
+                author_time: signature.when().seconds(),
+                summary,
+                start_line: hunk.final_start_line(),
+                line_count: hunk.lines_in_hunk(),
+                orig_start_line: hunk.orig_start_line(),
+                orig_path: String::from(orig_path),
+                is_boundary: hunk.is_boundary(),
+            });
+        }
+
+        let file_type = String::from(file_path.split(".").last().unwrap_or(""));
+        let content = String::from_utf8_lossy(blob.content());
+        let mut lines = vec![];
+        let mut hunk_index = 0;
+        for (i, line) in content.lines().enumerate() {
+            let line_number = i + 1;
+            while hunk_index + 1 < hunks.len() && line_number >= hunks[hunk_index + 1].start_line {
+                hunk_index += 1;
+            }
+            let hunk_info = match hunks.get(hunk_index) {
+                Some(h) => h,
+                None => bail!("Blame didn't return a hunk for line {}, the file may have changed while blaming.", line_number),
+            };
+            let mut line_string = String::from(line);
+            trim_newline(&mut line_string);
+            lines.push(BlameLineInfo {
+                line_number,
+                file_type: file_type.clone(),
+                content: html_escape::encode_text(&line_string).parse()?,
+                hunk_index,
+                sha: hunk_info.sha.clone(),
+                author_name: hunk_info.author_name.clone(),
+                author_time: hunk_info.author_time,
+                summary: hunk_info.summary.clone(),
+                orig_line_number: hunk_info.orig_start_line + (line_number - hunk_info.start_line),
+                orig_path: hunk_info.orig_path.clone(),
+            });
+        }
+
+        Ok(BlameInfo {
+            file_path: file_path.clone(),
+            sha: commit.id().to_string(),
+            lines,
+            hunks,
+        })
+    }
+
+    pub fn git_stage_all(&self) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let mut index = repo.index()?;
+        index.add_all(&["."], IndexAddOption::DEFAULT, None)?;
+        index.write()?;
+
+        Ok(())
+    }
+
+    fn git_commit(&self, full_message: String, author: &Signature, committer: &Signature, parent_commits: Vec<&Commit>) -> Result<()> {
+        if !self.has_staged_changes()? {
+            bail!("Attempted to commit with no staged changes! Maybe stage some changes first?");
+        }
+
+        let repo = self.borrow_repo()?;
+
+        let mut index = repo.index()?;
+        let tree_oid = index.write_tree()?;
+        index.write()?;
+        let tree = repo.find_tree(tree_oid)?;
+
+        let expected_head_opt = parent_commits.first().map(|c| c.id());
+        self.create_commit(author, committer, &*full_message, &tree, parent_commits.as_slice(), expected_head_opt)?;
+
+        Ok(())
+    }
+
+    fn git_amend_head(&self, author_opt: Option<&Signature>, committer: &Signature, message_opt: Option<&str>) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let head_commit = match repo.head()?.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD has no target commit, there's nothing to amend."),
+        };
+
+        let mut index = repo.index()?;
+        let tree_oid = index.write_tree()?;
+        index.write()?;
+        let tree = repo.find_tree(tree_oid)?;
+
+        let original_author = head_commit.author();
+        let author = author_opt.unwrap_or(&original_author);
+        let message = match message_opt {
+            Some(m) => m,
+            None => GitManager::get_utf8_string(head_commit.message(), "Commit Message")?,
+        };
+        let parents: Vec<Commit> = head_commit.parents().collect();
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+
+        self.create_commit(author, committer, message, &tree, parent_refs.as_slice(), Some(head_commit.id()))?;
+
+        Ok(())
+    }
+
+    // expected_head_opt is the commit HEAD should still point to (None when HEAD is unborn), so a HEAD that moved
+    // in the meantime isn't overwritten.
+    fn create_commit(&self, author: &Signature, committer: &Signature, message: &str, tree: &Tree, parent_commits: &[&Commit], expected_head_opt: Option<Oid>) -> Result<Oid> {
+        let repo = self.borrow_repo()?;
+        let config = config_manager::get_config()?;
+
+        let commit_oid;
+        if self.should_sign("commit.gpgsign", config.borrow_sign_commits())? {
+            let commit_buffer = repo.commit_create_buffer(author, committer, message, tree, parent_commits)?;
+            let commit_content = GitManager::get_utf8_string(commit_buffer.as_str(), "Commit Buffer")?;
+            let signature = self.sign_buffer(commit_content, committer)?;
+            commit_oid = repo.commit_signed(commit_content, &*signature, None)?;
+        } else {
+            commit_oid = repo.commit(None, author, committer, message, tree, parent_commits)?;
+        }
+
+        // HEAD is moved separately since amending or signing doesn't produce a commit on top of the current HEAD.
+        let head_ref = repo.find_reference("HEAD")?;
+        let head_target_name = String::from(head_ref.symbolic_target().unwrap_or("HEAD"));
+        let summary = message.lines().next().unwrap_or("");
+        let log_message = format!("oxidized_git commit: {}", summary);
+        let update_result = match expected_head_opt {
+            Some(expected_head_oid) => repo.reference_matching(&*head_target_name, commit_oid, true, expected_head_oid, &*log_message),
+            None => repo.reference(&*head_target_name, commit_oid, false, &*log_message),
+        };
+        if let Err(e) = update_result {
+            if e.code() == ErrorCode::Modified || e.code() == ErrorCode::Exists {
+                bail!("HEAD was moved by something else while committing, so the commit was created ({}) but HEAD was left alone.", commit_oid);
+            }
+            return Err(e.into());
+        }
+
+        Ok(commit_oid)
+    }
+
+    fn should_sign(&self, git_config_name: &str, config_value: &Option<bool>) -> Result<bool> {
+        let repo = self.borrow_repo()?;
+
+        // The git config is the most specific setting, so only fall back to the app config when it isn't set.
+        match repo.config()?.get_bool(git_config_name) {
+            Ok(b) => Ok(b),
+            Err(e) => {
+                if e.code() == ErrorCode::NotFound {
+                    Ok(config_value.unwrap_or(false))
+                } else {
+                    Err(e.into())
+                }
+            },
+        }
+    }
+
+    fn sign_buffer(&self, buffer: &str, signer: &Signature) -> Result<String> {
+        let repo = self.borrow_repo()?;
+        let git_config = repo.config()?;
+        let config = config_manager::get_config()?;
+
+        let signing_format = match git_config.get_string("gpg.format") {
+            Ok(s) => s,
+            Err(_) => config.borrow_signing_format().clone().unwrap_or(String::from("openpgp")),
+        };
+        let signing_key_opt = match git_config.get_string("user.signingkey") {
+            Ok(s) => Some(s),
+            Err(_) => config.borrow_signing_key().clone(),
+        };
+
+        if signing_format == "openpgp" || signing_format == "x509" {
+            let program = if signing_format == "openpgp" {
+                git_config.get_string("gpg.openpgp.program").or(git_config.get_string("gpg.program")).unwrap_or(String::from("gpg"))
+            } else {
+                git_config.get_string("gpg.x509.program").unwrap_or(String::from("gpgsm"))
+            };
+            // Like git, default to the key matching the signer's identity.
+            let signing_key = match signing_key_opt {
+                Some(k) => k,
+                None => format!("{} <{}>", GitManager::get_utf8_string(signer.name(), "Signer Name")?, GitManager::get_utf8_string(signer.email(), "Signer Email")?),
+            };
+            GitManager::run_signing_program(&*program, &["--status-fd=2", "-bsau", &*signing_key], buffer)
+        } else if signing_format == "ssh" {
+            let program = git_config.get_string("gpg.ssh.program").unwrap_or(String::from("ssh-keygen"));
+            let signing_key = match signing_key_opt {
+                Some(k) => k,
+                None => bail!("SSH signing requires a signing key. Set user.signingkey in your git config or a signing key in your preferences."),
             };
+            let signing_key = signing_key.strip_prefix("key::").unwrap_or(&*signing_key);
+
+            // The key can either be a literal public key (with the private key in ssh-agent) or a path to a key file.
+            let is_literal_key = signing_key.starts_with("ssh-") || signing_key.starts_with("ecdsa-") || signing_key.starts_with("sk-");
+            if is_literal_key {
+                let mut key_path = std::env::temp_dir();
+                key_path.push(format!("oxidized_git_signing_key_{}.pub", std::process::id()));
+                fs::write(&key_path, signing_key)?;
+                let key_path_string = GitManager::get_utf8_string(key_path.to_str(), "Signing Key Path")?;
+                let result = GitManager::run_signing_program(&*program, &["-Y", "sign", "-n", "git", "-U", "-f", key_path_string], buffer);
+                fs::remove_file(&key_path)?;
+                result
+            } else {
+                let mut key_path_string = String::from(signing_key);
+                if let Some(relative_path) = signing_key.strip_prefix("~/") {
+                    if let Some(base_dirs) = BaseDirs::new() {
+                        let mut key_path = base_dirs.home_dir().to_path_buf();
+                        key_path.push(relative_path);
+                        key_path_string = String::from(GitManager::get_utf8_string(key_path.to_str(), "Signing Key Path")?);
+                    }
+                }
+                GitManager::run_signing_program(&*program, &["-Y", "sign", "-n", "git", "-f", &*key_path_string], buffer)
+            }
+        } else {
+            bail!("Signing format '{}' isn't supported. Choices are 'openpgp', 'x509', or 'ssh'", signing_format);
+        }
+    }
+
+    fn run_signing_program(program: &str, args: &[&str], buffer: &str) -> Result<String> {
+        let mut child = match Command::new(program).args(args).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+            Ok(c) => c,
+            Err(e) => bail!("Failed to run '{}' for signing, is it installed and on your PATH? {}", program, e),
+        };
+        match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(buffer.as_bytes())?,
+            None => bail!("Failed to send the data to sign to '{}'.", program),
+        };
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("Signing with '{}' failed: {}", program, String::from_utf8_lossy(&output.stderr));
+        }
+        let signature = String::from_utf8(output.stdout)?;
+        if signature.is_empty() {
+            bail!("Signing with '{}' didn't produce a signature.", program);
+        }
 
-            if repo.head_detached()? {
-                match repo.head()?.target() {
-                    Some(oid) => {
-                        if !oid_vec.contains(&oid) {
-                            oid_vec.push(oid);
-                        }
-                    },
-                    None => (),
-                };
-            }
+        Ok(signature)
+    }
 
-            // Sort Oids by date first
-            oid_vec.sort_by(|a, b| {
-                repo.find_commit(*b).unwrap().time().seconds().partial_cmp(&repo.find_commit(*a).unwrap().time().seconds()).unwrap()
-            });
+    fn format_signature_for_buffer(signature: &Signature) -> Result<String> {
+        let when = signature.when();
+        let offset = when.offset_minutes().abs();
+        Ok(format!(
+            "{} <{}> {} {}{:02}{:02}",
+            GitManager::get_utf8_string(signature.name(), "Signature Name")?,
+            GitManager::get_utf8_string(signature.email(), "Signature Email")?,
+            when.seconds(),
+            when.sign(),
+            offset / 60,
+            offset % 60,
+        ))
+    }
+
+    fn git_signed_tag(&self, name: &str, target: &Object, tagger: &Signature, message: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let tag_ref_name = format!("refs/tags/{}", name);
+        if !Reference::is_valid_name(&*tag_ref_name) {
+            bail!("'{}' isn't a valid tag name.", name);
         }
 
-        if force_refresh {
-            self.old_graph_starting_shas = vec![];
+        let target_type = match target.kind() {
+            Some(k) => k.str(),
+            None => bail!("Couldn't determine the type of the object being tagged."),
+        };
+        let mut tag_content = format!("object {}\ntype {}\ntag {}\ntagger {}\n\n{}", target.id(), target_type, name, GitManager::format_signature_for_buffer(tagger)?, message);
+        if !tag_content.ends_with('\n') {
+            tag_content.push('\n');
         }
+        // Unlike commits, tag signatures are simply appended to the tag's message.
+        tag_content += &*self.sign_buffer(&*tag_content, tagger)?;
 
-        if self.old_shas_eq_sorted_new_oids(&oid_vec) {
-            return Ok(None);
+        let tag_oid = repo.odb()?.write(ObjectType::Tag, tag_content.as_bytes())?;
+        repo.reference(&*tag_ref_name, tag_oid, false, "oxidized_git tag: created signed tag")?;
+
+        Ok(())
+    }
+
+    pub fn git_commit_from_json(&self, json_string: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+        let signature = self.get_signature()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_string)?;
+        let summary = match json_hm.get("summaryText") {
+            Some(s) => s,
+            None => bail!("Front-end payload did not include summaryText"),
+        };
+        let message = match json_hm.get("messageText") {
+            Some(s) => s,
+            None => bail!("Front-end payload did not include messageText"),
+        };
+
+        let mut full_message = summary.clone();
+        if message != "" {
+            full_message += "\n\n";
+            full_message += message.as_str();
         }
 
-        // If you've reached here, the old and new starting oids are different. Update the old and perform the revwalk.
-        self.old_graph_starting_shas = oid_vec.iter().map(|new_oid| {
-            new_oid.to_string()
+        let mut parents = vec![];
+        match repo.head() {
+            Ok(head_ref) => {
+                if let Some(oid) = head_ref.target() {
+                    let commit = repo.find_commit(oid)?;
+                    parents.push(commit);
+                }
+            },
+            Err(e) => {
+                if e.code() != ErrorCode::UnbornBranch {
+                    return Err(e.into());
+                }
+            },
+        };
+
+        // This is a hack for getting a slice of borrowed commits later.
+        let parent_refs: Vec<&Commit> = parents.iter().map(|c| {
+            c
         }).collect();
 
+        self.git_commit(full_message, &signature, &signature, parent_refs)?;
+
+        // Like git, the squash message is only used for the next commit.
+        let squash_msg_path = GitManager::get_squash_msg_path(repo);
+        if squash_msg_path.exists() {
+            fs::remove_file(squash_msg_path)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_amend_info(&self) -> Result<AmendInfo> {
         let repo = self.borrow_repo()?;
-        let mut revwalk = repo.revwalk()?;
 
-        for oid in oid_vec {
-            revwalk.push(oid)?;
+        let head_ref = repo.head()?;
+        let head_commit = match head_ref.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD has no target commit, there's nothing to amend."),
+        };
+
+        let full_message = GitManager::get_utf8_string(head_commit.message(), "Commit Message")?;
+        let (message, _) = GitManager::get_message_without_summary(full_message);
+        let upstream_name = self.get_pushed_upstream_name(&head_ref, &head_commit)?;
+
+        Ok(AmendInfo {
+            summary: String::from(GitManager::get_utf8_string(head_commit.summary(), "Commit Summary")?),
+            message,
+            is_merge_commit: head_commit.parent_count() > 1,
+            is_pushed: upstream_name.is_some(),
+            upstream_name,
+        })
+    }
+
+    // If HEAD isn't ahead of its upstream, the commit being amended has already been pushed.
+    fn get_pushed_upstream_name(&self, head_ref: &Reference, head_commit: &Commit) -> Result<Option<String>> {
+        let repo = self.borrow_repo()?;
+        if !head_ref.is_branch() {
+            return Ok(None);
         }
-        revwalk.set_sorting(Sort::TOPOLOGICAL)?;
+        let head_branch = repo.find_branch(GitManager::get_utf8_string(head_ref.shorthand(), "Branch Name")?, BranchType::Local)?;
+        if let Ok(upstream) = head_branch.upstream() {
+            if let Some(upstream_oid) = upstream.get().target() {
+                let (ahead, _behind) = repo.graph_ahead_behind(head_commit.id(), upstream_oid)?;
+                if ahead == 0 {
+                    return Ok(Some(String::from(GitManager::get_utf8_string(upstream.get().shorthand(), "Branch Name")?)));
+                }
+            }
+        }
+        Ok(None)
+    }
 
-        let preferences = config_manager::get_config()?;
-        let limit_commits = match preferences.borrow_limit_commits() {
-            Some(b) => b.clone(),
-            None => bail!("limit_commits not present in config file!"),
+    pub fn git_amend_from_json(&self, json_string: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_string)?;
+        let summary = match json_hm.get("summaryText") {
+            Some(s) => s,
+            None => bail!("Front-end payload did not include summaryText"),
         };
-        let commit_count = match preferences.borrow_commit_count() {
-            Some(i) => i.clone(),
-            None => bail!("commit_count not present in config file!"),
+        let message = match json_hm.get("messageText") {
+            Some(s) => s,
+            None => bail!("Front-end payload did not include messageText"),
+        };
+        let reset_author_date = match json_hm.get("resetAuthorDate") {
+            Some(s) => s == "true",
+            None => bail!("Front-end payload did not include resetAuthorDate"),
+        };
+        let allow_merge_amend = match json_hm.get("allowMergeAmend") {
+            Some(s) => s == "true",
+            None => bail!("Front-end payload did not include allowMergeAmend"),
+        };
+        let allow_pushed_amend = match json_hm.get("allowPushedAmend") {
+            Some(s) => s == "true",
+            None => bail!("Front-end payload did not include allowPushedAmend"),
         };
 
-        let mut oid_list: Vec<Oid> = vec![];
-        for (i, commit_oid_result) in revwalk.enumerate() {
-            if limit_commits && i >= commit_count {
-                break;
+        let head_ref = repo.head()?;
+        let head_commit = match head_ref.target() {
+            Some(oid) => repo.find_commit(oid)?,
+            None => bail!("HEAD has no target commit, there's nothing to amend."),
+        };
+        if head_commit.parent_count() > 1 && !allow_merge_amend {
+            bail!("HEAD is a merge commit. Amending it will rewrite the merge, confirm that you want to do this first.");
+        }
+        if let Some(upstream_name) = self.get_pushed_upstream_name(&head_ref, &head_commit)? {
+            if !allow_pushed_amend {
+                bail!("HEAD has already been pushed to '{}'. Amending it will rewrite history others may have, confirm that you want to do this first.", upstream_name);
+            }
+        }
+
+        // With no new summary or message, the whole message of the commit being amended is kept. A new message
+        // without a summary keeps the old summary.
+        let mut full_message_opt = None;
+        if summary != "" || message != "" {
+            let mut full_message = if summary != "" {
+                summary.clone()
+            } else {
+                String::from(GitManager::get_utf8_string(head_commit.summary(), "Commit Summary")?)
+            };
+            if message != "" {
+                full_message += "\n\n";
+                full_message += message.as_str();
             }
-            oid_list.push(commit_oid_result?);
+            full_message_opt = Some(full_message);
+        }
+
+        let committer = self.get_signature()?;
+        let original_author = head_commit.author();
+        let mut author_opt = None;
+        if reset_author_date {
+            let author_name = GitManager::get_utf8_string(original_author.name(), "Author Name")?;
+            let author_email = GitManager::get_utf8_string(original_author.email(), "Author Email")?;
+            author_opt = Some(Signature::now(author_name, author_email)?);
         }
-        Ok(Some(oid_list))
+
+        self.git_amend_head(author_opt.as_ref(), &committer, full_message_opt.as_deref())?;
+
+        Ok(())
     }
 
-    pub fn get_commit_info(&self, json_str: &str) -> Result<CommitInfo> {
-        let sha_value: Value = serde_json::from_str(json_str)?;
-        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+    pub fn git_discard_changes(&self, json_str: &str) -> Result<()> {
         let repo = self.borrow_repo()?;
 
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
-        let commit_info = CommitInfo::from_commit(commit, repo)?;
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let path = match json_hm.get("path") {
+            Some(s) => s,
+            None => bail!("path was not included in the payload from the front-end"),
+        };
+        let change_type = match json_hm.get("change_type") {
+            Some(s) => s,
+            None => bail!("change_type was not included in the payload from the front-end"),
+        };
+        let status: u8 = match json_hm.get("status") {
+            Some(s) => s.parse()?,
+            None => bail!("status was not included in the payload from the front-end"),
+        };
 
-        Ok(commit_info)
+        let mut cb = CheckoutBuilder::new();
+        cb.path(path);
+        cb.force();
+
+        if change_type == "unstaged" && status == 7 {  // if unstaged and untracked need to stage it to discard.
+            self.git_stage(status, path)?;
+        } else if status == 4 {  // if renamed, need to discard the new file and old file.
+            let diff;
+            if change_type == "unstaged" {
+                diff = self.get_unstaged_changes()?;
+            } else if change_type == "staged" {
+                diff = self.get_staged_changes()?;
+            } else {
+                bail!("Attempting to discard a renamed file that's neither staged nor unstaged. This error should technically be impossible.");
+            }
+
+            let diff_delta = match diff.get_delta(GitManager::get_file_index_in_diff(&diff, path.as_str())?) {
+                Some(dd) => dd,
+                None => bail!("Couldn't find DiffDelta associated with the renamed file, unable to discard changes."),
+            };
+
+            let old_path = match diff_delta.old_file().path() {
+                Some(p) => p,
+                None => bail!("Couldn't find old file path of renamed file, unable to discard changes."),
+            };
+
+            cb.path(old_path);
+        }
+
+        repo.checkout_head(Some(&mut cb))?;
+
+        Ok(())
+    }
+
+    pub fn git_delete_local_branch(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let branch_shorthand = match json_hm.get("branch_shorthand") {
+            Some(s) => s,
+            None => bail!("branch_shorthand not included in payload from front-end!"),
+        };
+        let delete_remote_branch = match json_hm.get("delete_remote_branch") {
+            Some(s) => s == "true",
+            None => bail!("delete_remote_branch not included in payload from front-end!"),
+        };
+
+        let mut branch = repo.find_branch(branch_shorthand, BranchType::Local)?;
+
+        if delete_remote_branch {
+            let remote_branch = branch.upstream()?;
+            self.git_delete_remote_branch(remote_branch)?;
+        }
+
+        branch.delete()?;
+        Ok(())
+    }
+
+    fn git_delete_remote_branch(&self, remote_branch: Branch) -> Result<()> {
+        let repo = self.borrow_repo()?;
+
+        let branch_shorthand = GitManager::get_utf8_string(remote_branch.get().shorthand(), "Branch Shorthand")?;
+        let remote_branch_full_name = GitManager::get_utf8_string(remote_branch.get().name(), "Branch Name")?;
+
+        // Look for a local branch that already exists for the specified remote branch. If one exists,
+        // unset its upstream.
+        for local_b_result in repo.branches(Some(BranchType::Local))? {
+            let (mut local_b, _) = local_b_result?;
+            let local_upstream = match local_b.upstream() {
+                Ok(b) => b,
+                Err(_) => {
+                    continue;
+                },
+            };
+            let local_upstream_full_name = GitManager::get_utf8_string(local_upstream.get().name(), "Branch Name")?;
+            if local_upstream_full_name == remote_branch_full_name {
+                local_b.set_upstream(None)?;
+            }
+        }
+
+        let first_slash_index = match branch_shorthand.find("/") {
+            Some(i) => i,
+            None => bail!("Remote Branch doesn't seem to have a remote in its name?"),
+        };
+        let mut remote = repo.find_remote(&branch_shorthand[0..first_slash_index])?;
+        let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(GitManager::get_remote_callbacks(&credential_session));
+        let refspec = String::from(":refs/heads/") + &branch_shorthand[(first_slash_index + 1)..];
+        credential_session.finish(remote.push(&[refspec.as_str()], Some(&mut push_options)))?;
+        Ok(())
+    }
+
+    pub fn git_delete_remote_branch_from_json(&self, json_str: &str) -> Result<()> {
+        let branch_shorthand_value: Value = serde_json::from_str(json_str)?;
+        let branch_shorthand: &str = GitManager::get_string_from_serde_string(branch_shorthand_value.as_str())?;
+        let repo = self.borrow_repo()?;
+        let remote_branch = repo.find_branch(branch_shorthand, BranchType::Remote)?;
+        self.git_delete_remote_branch(remote_branch)?;
+        Ok(())
+    }
+
+    pub fn git_delete_tag(&self, json_str: &str) -> Result<()> {
+        // The payload can either be just the tag name or include a remote to delete it from too.
+        let json_value: Value = serde_json::from_str(json_str)?;
+        let delete_tag_options: DeleteTagOptions = match json_value.as_str() {
+            Some(tag_name) => DeleteTagOptions {
+                tag_name: String::from(tag_name),
+                remote_name: String::new(),
+            },
+            None => serde_json::from_value(json_value)?,
+        };
+        let repo = self.borrow_repo()?;
+
+        if delete_tag_options.remote_name != "" {
+            let mut remote = repo.find_remote(&delete_tag_options.remote_name)?;
+            let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+            let mut push_options = PushOptions::new();
+            push_options.remote_callbacks(GitManager::get_remote_callbacks(&credential_session));
+            let refspec = format!(":refs/tags/{}", delete_tag_options.tag_name);
+            credential_session.finish(remote.push(&[refspec.as_str()], Some(&mut push_options)))?;
+        }
+
+        match repo.tag_delete(&delete_tag_options.tag_name) {
+            Ok(()) => (),
+            Err(e) => {
+                // Allow deleting a tag that only exists on the remote.
+                if e.code() != ErrorCode::NotFound || delete_tag_options.remote_name == "" {
+                    return Err(e.into());
+                }
+            },
+        };
+        Ok(())
     }
 
-    fn has_conflicts(&self) -> Result<bool> {
-        let unstaged_diff = self.get_unstaged_changes()?;
-        let staged_diff = self.get_staged_changes()?;
+    pub fn get_tag_info(&self, json_str: &str) -> Result<TagInfo> {
+        let tag_name_value: Value = serde_json::from_str(json_str)?;
+        let tag_name: &str = GitManager::get_string_from_serde_string(tag_name_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let full_name = format!("refs/tags/{}", tag_name);
+        let tag_ref = repo.find_reference(&full_name)?;
+        let commit_sha = match tag_ref.peel_to_commit() {
+            Ok(c) => Some(c.id().to_string()),
+            Err(_) => None,
+        };
+        let tag_object = tag_ref.peel(ObjectType::Any)?;
+        let direct_object = match tag_ref.target() {
+            Some(oid) => repo.find_object(oid, None)?,
+            None => bail!("Tag '{}' doesn't point at anything.", tag_name),
+        };
 
-        for delta in unstaged_diff.deltas() {
-            if delta.status() == Delta::Conflicted {
-                return Ok(true);
+        let mut tag_info = TagInfo {
+            name: String::from(tag_name),
+            full_name: full_name.clone(),
+            is_annotated: false,
+            target_sha: tag_object.id().to_string(),
+            target_type: GitManager::get_object_type_string(tag_object.kind()),
+            commit_sha,
+            tagger_name: None,
+            tagger_email: None,
+            tagger_time: None,
+            message: None,
+        };
+        if let Some(tag) = direct_object.as_tag() {
+            tag_info.is_annotated = true;
+            tag_info.target_sha = tag.target_id().to_string();
+            tag_info.target_type = GitManager::get_object_type_string(tag.target_type());
+            if let Some(tagger) = tag.tagger() {
+                tag_info.tagger_name = Some(html_escape::encode_text(GitManager::get_utf8_string(tagger.name(), "Tagger Name")?).parse()?);
+                tag_info.tagger_email = Some(html_escape::encode_text(GitManager::get_utf8_string(tagger.email(), "Tagger Email")?).parse()?);
+                tag_info.tagger_time = Some(tagger.when().seconds());
             }
-        }
-
-        for delta in staged_diff.deltas() {
-            if delta.status() == Delta::Conflicted {
-                return Ok(true);
+            if let Some(message) = tag.message() {
+                tag_info.message = Some(html_escape::encode_text(message).parse()?);
             }
         }
 
-        Ok(false)
+        Ok(tag_info)
     }
 
-    fn has_unstaged_changes(&self) -> Result<bool> {
-        let diff = self.get_unstaged_changes()?;
-
-        if diff.stats()?.files_changed() > 0 {
-            Ok(true)
-        } else {
-            Ok(false)
+    fn get_object_type_string(object_type_opt: Option<ObjectType>) -> String {
+        match object_type_opt {
+            Some(object_type) => object_type.str().to_string(),
+            None => String::from("unknown"),
         }
     }
 
-    fn has_staged_changes(&self) -> Result<bool> {
-        let diff = self.get_staged_changes()?;
+    fn fetch_remote(&self, repo: &Repository, remote_name: &str) -> Result<()> {
+        let empty_refspecs: &[String] = &[];
+        let mut remote = repo.find_remote(remote_name)?;
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.download_tags(AutotagOption::All);
+        fetch_options.prune(FetchPrune::On);
+        let credential_session = self.new_credential_session(Some(repo), remote.url().unwrap_or(""));
+        fetch_options.remote_callbacks(self.get_remote_callbacks_with_progress(&credential_session, "fetch", remote_name));
+        credential_session.finish(remote.fetch(empty_refspecs, Some(&mut fetch_options), None)).map_err(|e| self.cancel_handle.map_git_error(e))?;
+        Ok(())
+    }
 
-        if diff.stats()?.files_changed() > 0 {
-            Ok(true)
-        } else {
-            Ok(false)
+    pub fn git_fetch(&self) -> Result<()> {
+        self.cancel_handle.start_step();
+        let repo = self.borrow_repo()?;
+        let remote_string_array = repo.remotes()?;
+        for remote_string_opt in remote_string_array.iter() {
+            let remote_string = GitManager::get_utf8_string(remote_string_opt, "Remote Name")?;
+            self.fetch_remote(repo, remote_string)?;
         }
+        Ok(())
     }
 
-    pub fn git_merge(&self, json_str: &str) -> Result<()> {
-        let sha_value: Value = serde_json::from_str(json_str)?;
-        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+    pub fn git_fetch_remote(&self, json_str: &str) -> Result<()> {
+        let remote_name_value: Value = serde_json::from_str(json_str)?;
+        let remote_name: &str = GitManager::get_string_from_serde_string(remote_name_value.as_str())?;
+        self.cancel_handle.start_step();
+        self.fetch_remote(self.borrow_repo()?, remote_name)?;
+        Ok(())
+    }
+
+    pub fn git_prune_remote(&self, json_str: &str) -> Result<()> {
+        let remote_name_value: Value = serde_json::from_str(json_str)?;
+        let remote_name: &str = GitManager::get_string_from_serde_string(remote_name_value.as_str())?;
         let repo = self.borrow_repo()?;
-        let annotated_commit = repo.find_annotated_commit(Oid::from_str(sha)?)?;
+        let mut remote = repo.find_remote(remote_name)?;
 
-        repo.merge(&[&annotated_commit], None, None)?;
+        // Pruning needs the remote's current list of refs, but nothing gets downloaded.
+        let credential_session = self.new_credential_session(Some(repo), remote.url().unwrap_or(""));
+        let mut connection = credential_session.finish(remote.connect_auth(Direction::Fetch, Some(GitManager::get_remote_callbacks(&credential_session)), None))?;
+        connection.remote().prune(Some(GitManager::get_remote_callbacks(&credential_session)))?;
+        Ok(())
+    }
 
-        if !self.has_conflicts()? {
-            if self.has_staged_changes()? {
-                let head_commit = match repo.head()?.target() {
-                    Some(oid) => repo.find_commit(oid)?,
-                    None => bail!("HEAD has no target, failed to commit after merging. It should fail earlier than this since there'd be no HEAD to merge into."),
-                };
-                let merge_parent_two = repo.find_commit(Oid::from_str(sha)?)?;
-                let parent_commits = vec![&head_commit, &merge_parent_two];
-                let committer = repo.signature()?;
+    pub fn git_fast_forward_branch(&self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo()?;
 
-                let mut short_sha = String::from(sha);
-                short_sha.truncate(5);
-                let mut head_short_sha = head_commit.id().to_string();
-                head_short_sha.truncate(5);
-                let message = String::from("Merge commit ") + short_sha.as_str() + " into commit " + head_short_sha.as_str();
+        let branch_shorthand_value: Value = serde_json::from_str(json_str)?;
+        let branch_shorthand: &str = GitManager::get_string_from_serde_string(branch_shorthand_value.as_str())?;
 
-                self.git_commit(message, &committer, &committer, parent_commits)?;
+        // Fetch first to make sure everything's up to date.
+        self.git_fetch()?;
 
-                repo.cleanup_state()?;
-            } else {
-                bail!("Merge commit had no changes, not really sure what would cause this...");
-            }
-        }
+        let mut local_branch = repo.find_branch(branch_shorthand, BranchType::Local)?;
 
-        Ok(())
-    }
+        let remote_branch = local_branch.upstream()?;
+        let remote_ref = remote_branch.get();
+        let remote_target = match remote_ref.target() {
+            Some(oid) => oid,
+            None => bail!("Remote branch is not targeting a commit, cannot pull."),
+        };
 
-    fn iterate_through_rebase(&self, repo: &Repository, rebase: &mut Rebase) -> Result<()> {
-        // Unfortunately, using 'rebase' like an iterator doesn't allow us to commit since
-        // rebase has to be borrowed mutably to commit.
-        let mut reached_end = false;
-        while !reached_end {
-            let step = rebase.next();
-            match step {
-                Some(r) => {
-                    r?;
+        let head = repo.head()?;
+        let local_ref = local_branch.get_mut();
 
-                    // If there are conflicts, need to let the user fix them before resuming
-                    // inside "git_continue_rebase"
-                    if self.has_conflicts()? || self.has_unstaged_changes()? {
-                        return Ok(());
-                    } else if self.has_staged_changes()? {
-                        rebase.commit(None, &repo.signature()?, None)?;
-                    }
-                },
-                None => reached_end = true,
+        // If fast-forwarding the branch currently checked out, need to update the working
+        // directory too.
+        if *local_ref == head {
+            let commit = match remote_ref.target() {
+                Some(oid) => repo.find_commit(oid)?,
+                None => bail!("Remote branch has no target commit."),
             };
+            let tree = commit.tree()?;
+            repo.checkout_tree(tree.as_object(), None)?;
         }
-        rebase.finish(None)?;
+
+        local_ref.set_target(remote_target, "oxidized_git fast-forward: setting new target for local ref")?;
 
         Ok(())
     }
 
-    pub fn git_rebase(&self, json_str: &str) -> Result<()> {
-        let sha_value: Value = serde_json::from_str(json_str)?;
-        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+    fn is_config_value_true(value: &str) -> bool {
+        // Anything other than an explicit false value enables rebasing (e.g. "true", "merges", or "interactive").
+        !["false", "no", "off", "0", ""].contains(&&*value.to_lowercase())
+    }
+
+    fn get_pull_strategy(&self, branch_name: &str) -> Result<String> {
         let repo = self.borrow_repo()?;
-        let annotated_commit = repo.find_annotated_commit(Oid::from_str(sha)?)?;
-        let mut rebase = repo.rebase(None, None, Some(&annotated_commit), None)?;
+        let git_config = repo.config()?;
+
+        // The branch's own settings take priority over the pull settings, which take priority over the app's preference.
+        // git has no per-branch pull.ff, so like git, a branch is fast-forward only when its merge options say so.
+        let branch_merge_options = git_config.get_string(&*format!("branch.{}.mergeoptions", branch_name)).unwrap_or(String::new());
+        let is_branch_ff_only = branch_merge_options.split_whitespace().any(|o| o == "--ff-only");
+        if let Ok(s) = git_config.get_string(&*format!("branch.{}.rebase", branch_name)) {
+            if GitManager::is_config_value_true(&*s) {
+                return Ok(String::from("rebase"));
+            } else if is_branch_ff_only {
+                return Ok(String::from("ff-only"));
+            }
+            return Ok(String::from("merge"));
+        } else if is_branch_ff_only {
+            return Ok(String::from("ff-only"));
+        }
 
-        self.iterate_through_rebase(repo, &mut rebase)?;
+        let is_pull_ff_only = git_config.get_string("pull.ff").map(|s| s == "only").unwrap_or(false);
+        if let Ok(s) = git_config.get_string("pull.rebase") {
+            if GitManager::is_config_value_true(&*s) {
+                return Ok(String::from("rebase"));
+            } else if is_pull_ff_only {
+                return Ok(String::from("ff-only"));
+            }
+            return Ok(String::from("merge"));
+        } else if is_pull_ff_only {
+            return Ok(String::from("ff-only"));
+        }
 
-        Ok(())
+        let config = config_manager::get_config()?;
+        match config.borrow_pull_strategy() {
+            Some(s) => {
+                if !PULL_STRATEGIES.contains(&&**s) {
+                    bail!("Pull strategy '{}' in your preferences isn't valid. Choices are 'merge', 'rebase', or 'ff-only'", s);
+                }
+                Ok(s.clone())
+            },
+            None => Ok(String::from("rebase")),
+        }
     }
 
-    pub fn git_cherrypick(&self, json_str: &str) -> Result<()> {
+    pub fn set_branch_pull_strategy(&self, json_str: &str) -> Result<()> {
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-
-        let sha = match json_hm.get("sha") {
+        let branch_name = match json_hm.get("branch_name") {
             Some(s) => s,
-            None => bail!("sha wasn't included in payload from the front-end."),
+            None => bail!("branch_name not included in payload from front-end."),
         };
-        let is_committing = match json_hm.get("isCommitting") {
-            Some(s) => s == "true",
-            None => bail!("isCommitting wasn't included in payload from the front-end."),
+        let pull_strategy = match json_hm.get("pull_strategy") {
+            Some(s) => s,
+            None => bail!("pull_strategy not included in payload from front-end."),
         };
 
         let repo = self.borrow_repo()?;
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
-
-        repo.cherrypick(&commit, None)?;
+        repo.find_branch(branch_name, BranchType::Local)?;
+        let mut git_config = repo.config()?.open_level(ConfigLevel::Local)?;
 
-        if !self.has_conflicts()? {
-            repo.cleanup_state()?;
+        if !["", "merge", "rebase", "ff-only"].contains(&pull_strategy.as_str()) {
+            bail!("Pull strategy '{}' isn't valid. Choices are 'merge', 'rebase', or 'ff-only'", pull_strategy);
         }
 
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+        // An empty strategy removes the branch's own setting so the global one is used.
+        let rebase_config_name = format!("branch.{}.rebase", branch_name);
+        if let Err(e) = git_config.remove(&*rebase_config_name) {
+            if e.code() != ErrorCode::NotFound {
+                return Err(e.into());
+            }
+        }
+        if pull_strategy != "" {
+            git_config.set_bool(&*rebase_config_name, pull_strategy == "rebase")?;
+        }
 
-        if is_committing && !self.has_conflicts()? && self.has_staged_changes()? {
-            let committer = repo.signature()?;
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("HEAD has no target, failed to commit after cherrypick."),
-            };
-            self.git_commit(String::from(GitManager::get_utf8_string(commit.message(), "Commit Message")?), &commit.author(), &committer, vec![&head_commit])?;
+        // Like "git config branch.<name>.mergeOptions --ff-only", which git pull uses too. Any other merge options are kept.
+        let merge_options_config_name = format!("branch.{}.mergeoptions", branch_name);
+        let merge_options = git_config.get_string(&*merge_options_config_name).unwrap_or(String::new());
+        let mut new_merge_options: Vec<&str> = merge_options.split_whitespace().filter(|o| *o != "--ff-only").collect();
+        if pull_strategy == "ff-only" {
+            new_merge_options.push("--ff-only");
+        }
+        if new_merge_options.is_empty() {
+            if let Err(e) = git_config.remove(&*merge_options_config_name) {
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+            }
+        } else {
+            git_config.set_str(&*merge_options_config_name, &*new_merge_options.join(" "))?;
         }
 
         Ok(())
     }
 
-    pub fn git_revert(&self, json_str: &str) -> Result<()> {
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-
-        let sha = match json_hm.get("sha") {
-            Some(s) => s,
-            None => bail!("sha wasn't included in payload from the front-end."),
-        };
-        let is_committing = match json_hm.get("isCommitting") {
-            Some(s) => s == "true",
-            None => bail!("isCommitting wasn't included in payload from the front-end."),
-        };
-
+    pub fn git_pull(&self) -> Result<()> {
         let repo = self.borrow_repo()?;
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
 
-        repo.revert(&commit, None)?;
+        // Fetch first to make sure everything's up to date.
+        self.git_fetch()?;
 
-        if !self.has_conflicts()? {
-            repo.cleanup_state()?;
+        let local_ref = repo.head()?;
+        if !local_ref.is_branch() {
+            bail!("HEAD isn't on a branch, checkout a branch to pull.");
         }
+        let local_shorthand = GitManager::get_utf8_string(local_ref.shorthand(), "Branch Name")?;
+        let local_branch = repo.find_branch(local_shorthand, BranchType::Local)?;
 
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+        let remote_branch = local_branch.upstream()?;
+        let remote_ref = remote_branch.get();
+        let remote_target = match remote_ref.target() {
+            Some(oid) => oid,
+            None => bail!("Remote branch is not targeting a commit, cannot pull."),
+        };
+        let remote_ac = repo.find_annotated_commit(remote_target)?;
 
-        if is_committing && !self.has_conflicts()? && self.has_staged_changes()? {
-            let committer = repo.signature()?;
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("HEAD has no target, failed to commit after revert."),
-            };
+        let pull_strategy = self.get_pull_strategy(local_shorthand)?;
+        let (ma, mp) = repo.merge_analysis(&[&remote_ac])?;
 
-            let mut new_full_message = String::from("Revert \"") + GitManager::get_utf8_string(commit.summary(), "Commit Summary")? + "\"";
-            let (message_without_summary, uses_crlf) = GitManager::get_message_without_summary(GitManager::get_utf8_string(commit.message(), "Commit Message")?);
-            if uses_crlf {
-                new_full_message += "\r\n\r\n";
-            } else {
-                new_full_message += "\n\n";
+        if ma.is_none() {
+            bail!("Merge analysis indicates no merge is possible. If you're reading this, your repository may be corrupted.");
+        } else if ma.is_unborn() {
+            bail!("The HEAD of the current repository is “unborn” and does not point to a valid commit. No pull can be performed, but the caller may wish to simply set HEAD to the target commit(s).");
+        } else if ma.is_up_to_date() {
+            return Ok(());
+        } else if ma.is_fast_forward() && !(pull_strategy == "merge" && mp.is_no_fast_forward()) {
+            return self.git_fast_forward_head(remote_target, "oxidized_git pull: Fast-forward");
+        } else if pull_strategy == "ff-only" {
+            bail!("Can't pull with fast-forward only, the local and remote branches have diverged. Choose a different pull strategy to pull.");
+        } else if pull_strategy == "merge" && mp.is_fastforward_only() {
+            bail!("Can't pull, the local and remote branches have diverged and merge.ff is set to 'only'. Choose a different pull strategy, or unset it by running 'git config --unset merge.ff'.");
+        } else if pull_strategy == "rebase" {
+            if self.has_uncommitted_changes()? {
+                bail!("You have uncommitted changes. Commit or stash them before pulling with rebase.");
             }
-            new_full_message += message_without_summary.as_str();
-
-            self.git_commit(new_full_message, &commit.author(), &committer, vec![&head_commit])?;
+            let mut rebase = repo.rebase(None, None, Some(&remote_ac), None)?;
+            // If there are conflicts, the rebase is left in progress so it can be continued or aborted.
+            if !self.iterate_through_rebase(&mut rebase)? {
+                bail!("The rebase stopped on conflicts. Resolve them, then continue or abort the rebase.");
+            }
+            return Ok(());
         }
 
-        Ok(())
-    }
-
-    pub fn git_abort(&self) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let head_commit = match repo.head()?.target() {
+        // Match git's pull message, which names the branch on the remote rather than the remote-tracking branch.
+        let local_ref_name = GitManager::get_utf8_string(local_ref.name(), "Branch Name")?;
+        let remote_name_buf = repo.branch_upstream_remote(local_ref_name)?;
+        let remote = repo.find_remote(GitManager::get_utf8_string(remote_name_buf.as_str(), "Remote Name")?)?;
+        let merge_ref_name = repo.config()?.get_string(&*format!("branch.{}.merge", local_shorthand))?;
+        let message = format!(
+            "Merge branch '{}' of {} into {}",
+            merge_ref_name.strip_prefix("refs/heads/").unwrap_or(&*merge_ref_name),
+            GitManager::get_utf8_string(remote.url(), "Remote URL")?,
+            local_shorthand,
+        );
+
+        let head_commit = match local_ref.target() {
             Some(oid) => repo.find_commit(oid)?,
-            None => bail!("HEAD doesn't have a target commit, cannot abort to HEAD"),
+            None => bail!("HEAD has no target, failed to pull."),
         };
+        repo.merge(&[&remote_ac], None, None)?;
+        fs::write(GitManager::get_merge_msg_path(repo), &*message)?;
 
-        repo.reset(head_commit.as_object(), ResetType::Hard, None)?;
-
-        repo.cleanup_state()?;
-
-        Ok(())
-    }
-
-    pub fn git_continue_cherrypick(&self) -> Result<()> {
+        // If there are conflicts, the merge is left in progress so it can be continued or aborted.
         if !self.has_conflicts()? {
-            let repo = self.borrow_repo()?;
-
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("HEAD doesn't have a target commit (which is where a cherrypick operation starts on), can't complete cherrypick."),
-            };
-
-            let mut cherrypick_head_file = repo.path().to_path_buf();
-            cherrypick_head_file.push("CHERRY_PICK_HEAD");
-            let cherrypick_head_string = fs::read_to_string(cherrypick_head_file)?;
-            let sha = cherrypick_head_string.trim();
-
-            let commit_from_op = repo.find_commit(Oid::from_str(sha)?)?;
-
-            let committer = repo.signature()?;
-
-            self.git_commit(String::from(GitManager::get_utf8_string(commit_from_op.message(), "Commit Message")?), &commit_from_op.author(), &committer, vec![&head_commit])?;
-
-            repo.cleanup_state()?;
+            let remote_commit = repo.find_commit(remote_target)?;
+            self.git_commit_merge(&*message, vec![&head_commit, &remote_commit])?;
         }
 
         Ok(())
     }
 
-    pub fn git_continue_revert(&self) -> Result<()> {
-        if !self.has_conflicts()? {
-            let repo = self.borrow_repo()?;
+    pub fn git_push(&self, push_options_json_opt: Option<&str>) -> Result<()> {
+        self.cancel_handle.start_step();
+        let repo = self.borrow_repo()?;
 
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("HEAD doesn't have a target commit (which is where a revert operation starts on), can't complete revert."),
+        let is_force;
+        let remote_name_from_frontend_opt;
+        let push_options: HashMap<String, String>;
+        if let Some(push_options_json) = push_options_json_opt {
+            push_options = serde_json::from_str(push_options_json)?;
+            is_force = match push_options.get("isForcePush") {
+                Some(s) => s == "true",
+                None => bail!("isForcePush not included in payload from front-end."),
+            };
+            remote_name_from_frontend_opt = match push_options.get("selectedRemote") {
+                Some(s) => Some(s.as_str()),
+                None => bail!("selectedRemote not included in payload from front-end."),
             };
+        } else {
+            is_force = false;
+            remote_name_from_frontend_opt = None;
+        }
 
-            let mut revert_file = repo.path().to_path_buf();
-            revert_file.push("REVERT_HEAD");
-            let revert_head_string = fs::read_to_string(revert_file)?;
-            let sha = revert_head_string.trim();
+        let local_ref = repo.head()?;
+        let local_full_name = GitManager::get_utf8_string(local_ref.name(), "Branch Name")?;
 
-            let commit_from_op = repo.find_commit(Oid::from_str(sha)?)?;
+        let mut is_creating_new_remote_branch = false;
+        let mut remote = match repo.branch_upstream_remote(local_full_name) {
+            Ok(b) => {
+                let remote_name = GitManager::get_utf8_string(b.as_str(), "Remote Name")?;
+                repo.find_remote(remote_name)?
+            },
+            Err(_e) => {
+                is_creating_new_remote_branch = true;
+                match remote_name_from_frontend_opt {
+                    Some(rn) => repo.find_remote(rn)?,
+                    None => bail!("Attempted to push with no upstream branch and no specified remote."),
+                }
+            },
+        };
 
-            let committer = repo.signature()?;
+        let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(self.get_remote_callbacks_with_progress(&credential_session, "push", GitManager::get_utf8_string(remote.name(), "Remote Name")?));
 
-            let mut new_full_message = String::from("Revert \"") + GitManager::get_utf8_string(commit_from_op.summary(), "Commit Summary")? + "\"";
-            let (message_without_summary, uses_crlf) = GitManager::get_message_without_summary(GitManager::get_utf8_string(commit_from_op.message(), "Commit Message")?);
-            if uses_crlf {
-                new_full_message += "\r\n\r\n";
-            } else {
-                new_full_message += "\n\n";
-            }
-            new_full_message += message_without_summary.as_str();
+        let mut sb = String::from(local_full_name);
+        if is_force {
+            sb.insert(0, '+');
+        }
 
-            self.git_commit(new_full_message, &commit_from_op.author(), &committer, vec![&head_commit])?;
+        credential_session.finish(remote.push(&[sb.as_str()], Some(&mut push_options))).map_err(|e| self.cancel_handle.map_git_error(e))?;
 
-            repo.cleanup_state()?;
+        if is_creating_new_remote_branch {
+            let local_branch_shorthand = GitManager::get_utf8_string(local_ref.shorthand(), "Branch Name")?;
+            let remote_name = GitManager::get_utf8_string(remote.name(), "Remote Name")?;
+            let new_remote_branch_shorthand = format!("{remote_name}/{local_branch_shorthand}");
+            let mut local_branch = repo.find_branch(local_branch_shorthand, BranchType::Local)?;
+            local_branch.set_upstream(Some(&*new_remote_branch_shorthand))?;
         }
 
         Ok(())
     }
 
-    pub fn git_continue_merge(&self) -> Result<()> {
-        if !self.has_conflicts()? {
-            let repo = self.borrow_repo()?;
+    pub fn git_push_tag(&self, json_str: &str) -> Result<()> {
+        self.cancel_handle.start_step();
+        let repo = self.borrow_repo()?;
 
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("HEAD doesn't have a target commit (which is where a merge operation starts on), can't complete merge."),
-            };
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let mut tag_full_name = match json_hm.get("tagFullName") {
+            Some(s) => s.clone(),
+            None => bail!("tagFullName not included in payload from front-end."),
+        };
+        let is_force = match json_hm.get("isForcePush") {
+            Some(s) => s == "true",
+            None => bail!("isForcePush not included in payload from front-end."),
+        };
+        let remote_name = match json_hm.get("selectedRemote") {
+            Some(s) => s.as_str(),
+            None => bail!("selectedRemote not included in payload from front-end."),
+        };
 
-            let mut merge_file = repo.path().to_path_buf();
-            merge_file.push("MERGE_HEAD");
-            let merge_head_string = fs::read_to_string(merge_file)?;
-            let sha = merge_head_string.trim();
+        let mut remote = repo.find_remote(remote_name)?;
+
+        let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(self.get_remote_callbacks_with_progress(&credential_session, "push", remote_name));
 
-            let commit_from_op = repo.find_commit(Oid::from_str(sha)?)?;
+        if is_force {
+            tag_full_name.insert(0, '+');
+        }
 
-            let mut short_sha = String::from(sha);
-            short_sha.truncate(5);
+        credential_session.finish(remote.push(&[tag_full_name.as_str()], Some(&mut push_options))).map_err(|e| self.cancel_handle.map_git_error(e))?;
 
-            let mut head_short_sha = head_commit.id().to_string();
-            head_short_sha.truncate(5);
-            let message = String::from("Merge commit ") + short_sha.as_str() + " into commit " + head_short_sha.as_str();
+        Ok(())
+    }
 
-            let committer = repo.signature()?;
-            self.git_commit(message, &committer, &committer, vec![&head_commit, &commit_from_op])?;
+    pub fn git_push_tags(&self, json_str: &str) -> Result<()> {
+        self.cancel_handle.start_step();
+        let push_tags_options: PushTagsOptions = serde_json::from_str(json_str)?;
+        let repo = self.borrow_repo()?;
 
-            repo.cleanup_state()?;
+        // libgit2 can't push with wildcard refspecs, so list every tag when pushing all of them.
+        let mut tag_full_names = push_tags_options.tag_full_names.clone();
+        if tag_full_names.is_empty() {
+            for tag_name_opt in repo.tag_names(None)?.iter() {
+                tag_full_names.push(format!("refs/tags/{}", GitManager::get_utf8_string(tag_name_opt, "Tag Name")?));
+            }
+            if tag_full_names.is_empty() {
+                bail!("There are no tags to push.");
+            }
         }
+        let force_prefix = if push_tags_options.is_force_push { "+" } else { "" };
+        let refspecs: Vec<String> = tag_full_names.iter().map(|n| format!("{}{}", force_prefix, n)).collect();
+
+        let mut remote = repo.find_remote(&push_tags_options.selected_remote)?;
+        let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(self.get_remote_callbacks_with_progress(&credential_session, "push", &push_tags_options.selected_remote));
+        credential_session.finish(remote.push(&refspecs, Some(&mut push_options))).map_err(|e| self.cancel_handle.map_git_error(e))?;
 
         Ok(())
     }
 
-    pub fn git_abort_rebase(&self) -> Result<()> {
-        let repo = self.borrow_repo()?;
-        let mut rebase = repo.open_rebase(None)?;
+    pub fn git_stash(&mut self, json_str: &str) -> Result<()> {
+        // The payload can either be just the stash message or include the stash options too.
+        let json_value: Value = serde_json::from_str(json_str)?;
+        let stash_options: StashOptions = match json_value.as_str() {
+            Some(message) => StashOptions {
+                message: String::from(message),
+                paths: vec![],
+                keep_index: false,
+                include_untracked: true,
+            },
+            None => serde_json::from_value(json_value)?,
+        };
+        let signature = self.get_signature()?;
+        let repo = self.borrow_repo_mut()?;
+
+        if !stash_options.paths.is_empty() {
+            GitManager::git_stash_paths(repo, &signature, &stash_options)?;
+        } else if stash_options.message == "" {
+            repo.stash_save2(&signature, None, Some(stash_options.get_stash_flags()))?;
+        } else {
+            repo.stash_save2(&signature, Some(&*stash_options.message), Some(stash_options.get_stash_flags()))?;
+        }
 
-        rebase.abort()?;
         Ok(())
     }
 
-    pub fn git_continue_rebase(&self) -> Result<()> {
-        let repo = self.borrow_repo()?;
-        let mut rebase = repo.open_rebase(None)?;
+    fn does_path_match_pathspecs(path: &str, pathspecs: &Vec<String>) -> bool {
+        pathspecs.iter().any(|p| {
+            let pathspec = p.trim_end_matches('/');
+            path == pathspec || path.starts_with(&*format!("{}/", pathspec))
+        })
+    }
 
-        // Need to make sure there are no conflicts and we've committed before continuing.
-        if self.has_conflicts()? || self.has_unstaged_changes()? {
-            return Ok(());
-        } else if self.has_staged_changes()? {
-            rebase.commit(None, &repo.signature()?, None)?;
+    fn get_diff_paths(diff: &Diff) -> Result<Vec<String>> {
+        let mut paths: Vec<String> = vec![];
+        for delta in diff.deltas() {
+            for path_opt in [delta.old_file().path(), delta.new_file().path()] {
+                if let Some(path) = path_opt {
+                    let path_string = String::from(GitManager::get_utf8_string(path.to_str(), "File Path")?);
+                    if !paths.contains(&path_string) {
+                        paths.push(path_string);
+                    }
+                }
+            }
         }
+        Ok(paths)
+    }
 
-        self.iterate_through_rebase(repo, &mut rebase)?;
+    // Builds a tree that's the base tree (or an empty tree) with the given paths taken from the source tree instead.
+    fn get_tree_with_paths_from(repo: &Repository, base_tree_opt: Option<&Tree>, source_tree: &Tree, paths: &Vec<String>) -> Result<Oid> {
+        let mut index = Index::new()?;
+        if let Some(base_tree) = base_tree_opt {
+            index.read_tree(base_tree)?;
+        }
+        for path in paths {
+            match source_tree.get_path(Path::new(path)) {
+                Ok(tree_entry) => {
+                    index.add(&IndexEntry {
+                        ctime: IndexTime::new(0, 0),
+                        mtime: IndexTime::new(0, 0),
+                        dev: 0,
+                        ino: 0,
+                        mode: tree_entry.filemode() as u32,
+                        uid: 0,
+                        gid: 0,
+                        file_size: 0,
+                        id: tree_entry.id(),
+                        flags: path.len().min(0xfff) as u16,
+                        flags_extended: 0,
+                        path: path.as_bytes().to_vec(),
+                    })?;
+                },
+                Err(e) => {
+                    if e.code() != ErrorCode::NotFound {
+                        return Err(e.into());
+                    }
+                    index.remove_path(Path::new(path))?;
+                },
+            };
+        }
+        Ok(index.write_tree_to(repo)?)
+    }
 
+    // Puts the stashed changes to the given paths back, staged ones into the index first and then the rest into the working directory.
+    fn restore_stashed_paths(repo: &Repository, index_tree: &Tree, stash_tree: &Tree, untracked_tree_opt: Option<&Tree>, tracked_paths: &Vec<String>, untracked_paths: &Vec<String>) -> Result<()> {
+        if !tracked_paths.is_empty() {
+            let mut index_checkout = CheckoutBuilder::new();
+            index_checkout.force();
+            let mut workdir_checkout = CheckoutBuilder::new();
+            workdir_checkout.force();
+            workdir_checkout.update_index(false);
+            for path in tracked_paths {
+                index_checkout.path(path);
+                workdir_checkout.path(path);
+            }
+            repo.checkout_tree(index_tree.as_object(), Some(&mut index_checkout))?;
+            repo.checkout_tree(stash_tree.as_object(), Some(&mut workdir_checkout))?;
+        }
+        if let Some(untracked_tree) = untracked_tree_opt {
+            if !untracked_paths.is_empty() {
+                let mut untracked_checkout = CheckoutBuilder::new();
+                untracked_checkout.force();
+                untracked_checkout.update_index(false);
+                for path in untracked_paths {
+                    untracked_checkout.path(path);
+                }
+                repo.checkout_tree(untracked_tree.as_object(), Some(&mut untracked_checkout))?;
+            }
+        }
         Ok(())
     }
 
-    pub fn git_reset(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+    // Builds the stash and untracked trees from the working directory using the repo's index, which is always reloaded
+    // from disk afterwards so none of this is left in it.
+    fn get_stash_trees_from_workdir(repo: &Repository, index_tree: &Tree, unstaged_paths: &Vec<String>, untracked_paths: &Vec<String>) -> Result<(Oid, Option<Oid>)> {
+        let workdir = match repo.workdir() {
+            Some(d) => d,
+            None => bail!("Can't stash in a bare repository."),
+        };
+        let mut index = repo.index()?;
+        let trees_result = (|| -> Result<(Oid, Option<Oid>)> {
+            index.read_tree(index_tree)?;
+            for path in unstaged_paths {
+                if workdir.join(path).symlink_metadata().is_ok() {
+                    index.add_path(Path::new(path))?;
+                } else {
+                    index.remove_path(Path::new(path))?;
+                }
+            }
+            let stash_tree_oid = index.write_tree()?;
+            if untracked_paths.is_empty() {
+                return Ok((stash_tree_oid, None));
+            }
+            index.clear()?;
+            for path in untracked_paths {
+                index.add_path(Path::new(path))?;
+            }
+            Ok((stash_tree_oid, Some(index.write_tree()?)))
+        })();
+        index.read(true)?;
+        trees_result
+    }
 
-        let sha = match json_hm.get("sha") {
-            Some(s) => s,
-            None => bail!("sha wasn't included in payload from the front-end."),
+    // Stashing only some paths builds the stash commits here since libgit2's own path limited stash loses staged changes
+    // to other files. The new stash is only added to the stash list once its changes are out of the working directory.
+    fn git_stash_paths(repo: &Repository, signature: &Signature, stash_options: &StashOptions) -> Result<()> {
+        let head_ref = match repo.head() {
+            Ok(r) => r,
+            Err(e) if e.code() == ErrorCode::UnbornBranch => bail!("You can't stash before the first commit."),
+            Err(e) => return Err(e.into()),
         };
-        let reset_type_string = match json_hm.get("type") {
-            Some(s) => s,
-            None => bail!("type wasn't included in payload from the front-end."),
+        let head_commit = head_ref.peel_to_commit()?;
+        let head_tree = head_commit.tree()?;
+        let branch_name = if repo.head_detached()? {
+            "(no branch)"
+        } else {
+            GitManager::get_utf8_string(head_ref.shorthand(), "Branch Name")?
         };
+        let head_description = format!("{}: {} {}", branch_name, &head_commit.id().to_string()[..7], GitManager::get_utf8_string(head_commit.summary(), "Commit Summary")?);
 
-        let reset_type;
-        if reset_type_string == "soft" {
-            reset_type = ResetType::Soft;
-        } else if reset_type_string == "mixed" {
-            reset_type = ResetType::Mixed;
-        } else if reset_type_string == "hard" {
-            reset_type = ResetType::Hard;
-        } else {
-            bail!("type from front-end payload isn't a valid option. Choices are 'soft', 'mixed', or 'hard'");
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            bail!("You can't stash while there are conflicts.");
+        }
+        let full_index_tree = repo.find_tree(index.write_tree()?)?;
+        let staged_paths: Vec<String> = GitManager::get_diff_paths(&repo.diff_tree_to_tree(Some(&head_tree), Some(&full_index_tree), None)?)?
+            .into_iter()
+            .filter(|p| GitManager::does_path_match_pathspecs(p, &stash_options.paths))
+            .collect();
+        let mut workdir_diff_options = DiffOptions::new();
+        workdir_diff_options.include_untracked(stash_options.include_untracked);
+        workdir_diff_options.recurse_untracked_dirs(true);
+        let mut unstaged_paths: Vec<String> = vec![];
+        let mut untracked_paths: Vec<String> = vec![];
+        for delta in repo.diff_index_to_workdir(Some(&index), Some(&mut workdir_diff_options))?.deltas() {
+            if let Some(path) = delta.new_file().path().or(delta.old_file().path()) {
+                let path_string = String::from(GitManager::get_utf8_string(path.to_str(), "File Path")?);
+                if !GitManager::does_path_match_pathspecs(&path_string, &stash_options.paths) {
+                    continue;
+                }
+                if delta.status() == Delta::Untracked {
+                    untracked_paths.push(path_string);
+                } else {
+                    unstaged_paths.push(path_string);
+                }
+            }
+        }
+        if staged_paths.is_empty() && unstaged_paths.is_empty() && untracked_paths.is_empty() {
+            bail!("None of the selected paths have changes to stash.");
+        }
+        let mut tracked_paths = staged_paths.clone();
+        for path in &unstaged_paths {
+            if !tracked_paths.contains(path) {
+                tracked_paths.push(path.clone());
+            }
         }
 
-        let commit = repo.find_commit(Oid::from_str(sha)?)?;
+        // Only the selected changes go into the stash, so everything else in it matches HEAD.
+        let index_tree = repo.find_tree(GitManager::get_tree_with_paths_from(repo, Some(&head_tree), &full_index_tree, &staged_paths)?)?;
+        let (stash_tree_oid, untracked_tree_oid_opt) = GitManager::get_stash_trees_from_workdir(repo, &index_tree, &unstaged_paths, &untracked_paths)?;
+        let stash_tree = repo.find_tree(stash_tree_oid)?;
+        let untracked_tree_opt = match untracked_tree_oid_opt {
+            Some(oid) => Some(repo.find_tree(oid)?),
+            None => None,
+        };
 
-        repo.reset(commit.as_object(), reset_type, None)?;
+        let index_oid = repo.commit(None, signature, signature, &format!("index on {}\n", head_description), &index_tree, &[&head_commit])?;
+        let mut parents = vec![head_commit.clone(), repo.find_commit(index_oid)?];
+        if let Some(untracked_tree) = &untracked_tree_opt {
+            let untracked_oid = repo.commit(None, signature, signature, &format!("untracked files on {}\n", head_description), untracked_tree, &[])?;
+            parents.push(repo.find_commit(untracked_oid)?);
+        }
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+        let stash_message = if stash_options.message == "" {
+            format!("WIP on {}", head_description)
+        } else {
+            format!("On {}: {}", branch_name, stash_options.message)
+        };
+        let stash_oid = repo.commit(None, signature, signature, &format!("{}\n", stash_message), &stash_tree, &parent_refs)?;
+
+        // The existing stashes are left alone, the new one is just appended to the reflog that the stash list comes from.
+        let stash_result = (|| -> Result<()> {
+            let mut remove_checkout = CheckoutBuilder::new();
+            remove_checkout.force();
+            remove_checkout.remove_untracked(true);
+            for path in tracked_paths.iter().chain(untracked_paths.iter()) {
+                remove_checkout.path(path);
+            }
+            let target_tree = if stash_options.keep_index { &index_tree } else { &head_tree };
+            repo.checkout_tree(target_tree.as_object(), Some(&mut remove_checkout))?;
+            repo.reference_ensure_log("refs/stash")?;
+            repo.reference("refs/stash", stash_oid, true, &stash_message)?;
+            Ok(())
+        })();
+        if let Err(e) = stash_result {
+            GitManager::restore_stashed_paths(repo, &index_tree, &stash_tree, untracked_tree_opt.as_ref(), &tracked_paths, &untracked_paths)?;
+            return Err(e);
+        }
 
         Ok(())
     }
 
-    pub fn git_add_remote(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
+    pub fn git_apply_stash(&mut self, json_str: &str) -> Result<()> {
+        let repo = self.borrow_repo_mut()?;
 
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let remote_name = match json_hm.get("remote_name") {
-            Some(s) => s,
-            None => bail!("remote_name not included in payload from the front-end"),
+        let index = match json_hm.get("index") {
+            Some(s) => {
+                s.parse::<usize>()?
+            },
+            None => bail!("index not included in payload from front-end."),
         };
-        let remote_url = match json_hm.get("remote_url") {
-            Some(s) => s,
-            None => bail!("remote_url not included in payload from the front-end"),
+        let delete_stash = match json_hm.get("delete_stash") {
+            Some(s) => s == "true",
+            None => bail!("delete_stash not included in payload from front-end."),
+        };
+        // Older front-ends don't send this, so it's optional.
+        let reinstate_index = match json_hm.get("reinstate_index") {
+            Some(s) => s == "true",
+            None => false,
         };
 
-        repo.remote(remote_name.as_str(), remote_url.as_str())?;
-
-        Ok(())
-    }
-
-    fn git_checkout(&self, local_ref: &Reference) -> Result<()> {
-        let repo = self.borrow_repo()?;
+        let mut stash_apply_options = StashApplyOptions::new();
+        if reinstate_index {
+            stash_apply_options.reinstantiate_index();
+        }
 
-        let local_full_name = GitManager::get_utf8_string(local_ref.name(), "Branch Name")?;
-        let commit = match local_ref.target() {
-            Some(oid) => repo.find_commit(oid)?,
-            None => bail!("Trying to check out branch that has no target commit."),
-        };
-        let tree = commit.tree()?;
+        if delete_stash {
+            repo.stash_pop(index, Some(&mut stash_apply_options))?;
+        } else {
+            repo.stash_apply(index, Some(&mut stash_apply_options))?;
+        }
 
-        repo.checkout_tree(tree.as_object(), None)?;
-        repo.set_head(local_full_name)?;
         Ok(())
     }
 
-    pub fn git_checkout_from_json(&self, json_str: &str) -> Result<()> {
-        let ref_name_value: Value = serde_json::from_str(json_str)?;
-        let ref_name: &str = GitManager::get_string_from_serde_string(ref_name_value.as_str())?;
-        self.git_checkout(&self.borrow_repo()?.find_reference(ref_name)?)?;
-        Ok(())
+    fn get_stash_oid(repo: &Repository, index: usize) -> Result<Oid> {
+        let stash_reflog = match repo.reflog("refs/stash") {
+            Ok(r) => r,
+            Err(e) if e.code() == ErrorCode::NotFound => bail!("There are no stashes."),
+            Err(e) => return Err(e.into()),
+        };
+        match stash_reflog.get(index) {
+            Some(reflog_entry) => Ok(reflog_entry.id_new()),
+            None => bail!("Stash {} doesn't exist.", index),
+        }
     }
 
-    pub fn git_checkout_detached_head(&self, json_str: &str) -> Result<()> {
-        let sha_value: Value = serde_json::from_str(json_str)?;
-        let sha: &str = GitManager::get_string_from_serde_string(sha_value.as_str())?;
+    pub fn get_stash_changes(&self, json_str: &str) -> Result<Vec<ParseableDiffDelta>> {
+        let stash_index_str_value: Value = serde_json::from_str(json_str)?;
+        let stash_index_str: &str = GitManager::get_string_from_serde_string(stash_index_str_value.as_str())?;
         let repo = self.borrow_repo()?;
 
-        let oid = Oid::from_str(sha)?;
-        let tree = repo.find_commit(oid)?.tree()?;
+        let stash_oid = GitManager::get_stash_oid(repo, stash_index_str.parse::<usize>()?)?;
+        let stash_commit = repo.find_commit(stash_oid)?;
 
-        repo.checkout_tree(tree.as_object(), None)?;
-        repo.set_head_detached(oid)?;
-        Ok(())
+        get_parseable_diff_delta(get_stash_diff(&stash_commit, repo)?)
     }
 
-    pub fn git_checkout_remote(&self, json_string: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_string)?;
-        let remote_branch_shortname = match json_hm.get("branch_shorthand") {
-            Some(n) => n,
-            None => bail!("JSON Data is missing branch_shorthand attribute."),
+    pub fn git_branch_from_stash(&mut self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let index = match json_hm.get("index") {
+            Some(s) => s.parse::<usize>()?,
+            None => bail!("index not included in payload from front-end."),
         };
-        let remote_branch_full_name = match json_hm.get("full_branch_name") {
-            Some(n) => n,
-            None => bail!("JSON Data is missing full_branch_name attribute."),
+        let branch_name = match json_hm.get("branch_name") {
+            Some(s) => s,
+            None => bail!("branch_name not included in payload from front-end."),
         };
 
-        // Look for a local branch that already exists for the specified remote branch. If one exists,
-        // check it out instead.
-        for local_b_result in repo.branches(Some(BranchType::Local))? {
-            let (local_b, _) = local_b_result?;
-            let local_upstream = match local_b.upstream() {
-                Ok(b) => b,
-                Err(_) => {
-                    continue;
-                },
-            };
-            let local_upstream_full_name = GitManager::get_utf8_string(local_upstream.get().name(), "Branch Name")?;
-            if local_upstream_full_name == remote_branch_full_name {
-                return self.git_checkout(local_b.get());
-            }
-        }
-
-        // If there's no local branch, create a new one with the name used by the remote branch.
-        let remote_branch_name_parts: Vec<&str> = remote_branch_shortname.split("/").collect();
-        let mut local_branch_shortname = String::new();
-        for i in 1..remote_branch_name_parts.len() {
-            local_branch_shortname += remote_branch_name_parts[i];
-            if i < remote_branch_name_parts.len() - 1 {
-                local_branch_shortname += "/";
-            }
+        // Like "git stash branch", the branch starts from the commit the stash was made on so it always applies cleanly.
+        {
+            let repo = self.borrow_repo()?;
+            let stash_commit = repo.find_commit(GitManager::get_stash_oid(repo, index)?)?;
+            let base_commit = stash_commit.parent(0)?;
+            let new_branch = repo.branch(branch_name, &base_commit, false)?;
+            self.git_checkout(new_branch.get())?;
         }
-        let remote_branch = repo.find_branch(remote_branch_shortname, BranchType::Remote)?;
-        let commit = match remote_branch.get().target() {
-            Some(oid) => repo.find_commit(oid)?,
-            None => bail!("Selected remote branch isn't targeting a commit, can't checkout!"),
-        };
-        let mut local_branch = repo.branch(&*local_branch_shortname, &commit, false)?;
-        local_branch.set_upstream(Some(remote_branch_shortname))?;
-
-        self.git_checkout(local_branch.get())
-    }
-
-    fn git_stage(&self, status: u8, path: &String) -> Result<()> {
-        let repo = self.borrow_repo()?;
 
-        let mut index = repo.index()?;
-        if status == 2 {  // If file is deleted
-            index.remove_path(path.as_ref())?;
-        } else {
-            index.add_path(path.as_ref())?;
-        }
-        index.write()?;
+        let repo = self.borrow_repo_mut()?;
+        let mut stash_apply_options = StashApplyOptions::new();
+        stash_apply_options.reinstantiate_index();
+        repo.stash_pop(index, Some(&mut stash_apply_options))?;
 
         Ok(())
     }
 
-    pub fn git_stage_from_json(&self, json_str: &str) -> Result<()> {
-        let diff_delta: ParseableDiffDelta = serde_json::from_str(json_str)?;
+    pub fn git_delete_stash(&mut self, json_str: &str) -> Result<()> {
+        let stash_index_str_value: Value = serde_json::from_str(json_str)?;
+        let stash_index_str: &str = GitManager::get_string_from_serde_string(stash_index_str_value.as_str())?;
+        let repo = self.borrow_repo_mut()?;
 
-        self.git_stage(diff_delta.get_status(), diff_delta.get_path())?;
+        let index = stash_index_str.parse::<usize>()?;
+
+        repo.stash_drop(index)?;
 
         Ok(())
     }
 
-    pub fn git_unstage(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-        let diff_delta: ParseableDiffDelta = serde_json::from_str(json_str)?;
-
-        let mut index = repo.index()?;
-        let status = diff_delta.get_status();
-        if status == 2 || status == 3 {  // If file is deleted or modified
-            let head_commit = match repo.head()?.target() {
-                Some(oid) => {
-                    repo.find_commit(oid)?
-                },
-                None => bail!("Head has no target commit"),
-            };
-            repo.reset_default(Some(head_commit.as_object()), [diff_delta.get_path()])?;
+    fn get_selected_submodules<'a>(repo: &'a Repository, path: &str) -> Result<Vec<Submodule<'a>>> {
+        if path == "" {
+            Ok(repo.submodules()?)
         } else {
-            index.remove_path(diff_delta.get_path().as_ref())?;
+            Ok(vec![repo.find_submodule(path)?])
         }
-        index.write()?;
-
-        Ok(())
-    }
-
-    fn set_diff_find_similar(diff: &mut Diff) -> Result<()> {
-        let mut opts = DiffFindOptions::new();
-        opts.renames(true);
-        opts.copies(true);
-
-        diff.find_similar(Some(&mut opts))?;
-        Ok(())
     }
 
-    pub fn get_unstaged_changes(&self) -> Result<Diff> {
+    pub fn get_submodules(&self) -> Result<Vec<SubmoduleInfo>> {
         let repo = self.borrow_repo()?;
+        let config = repo.config()?;
+
+        let mut submodule_infos: Vec<SubmoduleInfo> = vec![];
+        for submodule in repo.submodules()? {
+            let name = String::from(GitManager::get_utf8_string(submodule.name(), "Submodule Name")?);
+            let path = String::from(GitManager::get_utf8_string(submodule.path().to_str(), "Submodule Path")?);
+            let submodule_status = repo.submodule_status(&*name, SubmoduleIgnore::None)?;
+            // A submodule is initialized once its url has been copied into the repo's git config.
+            let is_initialized = match config.get_string(&*format!("submodule.{}.url", name)) {
+                Ok(_) => true,
+                Err(e) => {
+                    if e.code() != ErrorCode::NotFound {
+                        return Err(e.into());
+                    }
+                    false
+                },
+            };
 
-        let mut diff_options = DiffOptions::new();
-        diff_options.include_untracked(true);
-        diff_options.recurse_untracked_dirs(true);
-        diff_options.show_untracked_content(true);
-
-        let mut diff = repo.diff_index_to_workdir(None, Some(&mut diff_options))?;
-        GitManager::set_diff_find_similar(&mut diff)?;
+            submodule_infos.push(SubmoduleInfo {
+                name,
+                path,
+                url: submodule.url().map(String::from),
+                branch: submodule.branch().map(String::from),
+                recorded_sha: submodule.head_id().map(|oid| oid.to_string()),
+                index_sha: submodule.index_id().map(|oid| oid.to_string()),
+                checked_out_sha: submodule.workdir_id().map(|oid| oid.to_string()),
+                is_initialized,
+                is_checked_out: submodule.open().is_ok(),
+                is_commit_changed: submodule_status.is_wd_modified(),
+                is_dirty: submodule_status.contains(SubmoduleStatus::WD_INDEX_MODIFIED) || submodule_status.is_wd_wd_modified() || submodule_status.is_wd_untracked(),
+            });
+        }
 
-        Ok(diff)
+        Ok(submodule_infos)
     }
 
-    pub fn get_staged_changes(&self) -> Result<Diff> {
+    pub fn git_init_submodules(&self, json_str: &str) -> Result<()> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path: &str = GitManager::get_string_from_serde_string(path_value.as_str())?;
         let repo = self.borrow_repo()?;
 
-        let mut tree = None;
-        match repo.head() {
-            Ok(head_ref) => {
-                let commit = match head_ref.target() {
-                    Some(oid) => Some(repo.find_commit(oid)?),
-                    None => None,
-                };
-                tree = match commit {
-                    Some(c) => Some(c.tree()?),
-                    None => None,
-                };
-            },
-            Err(e) => {
-                if e.code() != ErrorCode::UnbornBranch {
-                    return Err(e.into());
-                }
-            },
-        };
-
-        let mut diff = repo.diff_tree_to_index(tree.as_ref(), None, None)?;
-        GitManager::set_diff_find_similar(&mut diff)?;
+        // An empty path means every submodule.
+        for mut submodule in GitManager::get_selected_submodules(repo, path)? {
+            submodule.init(false)?;
+        }
 
-        Ok(diff)
+        Ok(())
     }
 
-    fn get_file_index_in_diff(diff: &Diff, path: &str) -> Result<usize> {
-        let file_index_opt = diff.deltas().position(|dd| {
-            match dd.new_file().path() {
-                Some(p) => {
-                    match p.to_str() {
-                        Some(s) => path == s,
-                        None => false,
-                    }
-                },
-                None => false,
+    fn update_submodules(&self, repo: &Repository, path: &str, init: bool, recursive: bool) -> Result<()> {
+        for mut submodule in GitManager::get_selected_submodules(repo, path)? {
+            let name = String::from(GitManager::get_utf8_string(submodule.name(), "Submodule Name")?);
+            // Like git, submodules that haven't been initialized are skipped unless they're being initialized now.
+            if path == "" && !init && repo.config()?.get_string(&*format!("submodule.{}.url", name)).is_err() {
+                continue;
+            }
+
+            let credential_session = self.new_credential_session(Some(repo), submodule.url().unwrap_or(""));
+            let mut fetch_options = FetchOptions::new();
+            fetch_options.remote_callbacks(GitManager::get_remote_callbacks(&credential_session));
+            let mut submodule_update_options = SubmoduleUpdateOptions::new();
+            submodule_update_options.fetch(fetch_options);
+            credential_session.finish(submodule.update(init, Some(&mut submodule_update_options)))?;
+
+            if recursive {
+                let submodule_repo = submodule.open()?;
+                self.update_submodules(&submodule_repo, "", init, recursive)?;
             }
-        });
-        match file_index_opt {
-            Some(i) => Ok(i),
-            None => bail!("Selected file not found."),
         }
+
+        Ok(())
     }
 
-    pub fn get_file_diff(&self, json_str: &str) -> Result<FileInfo> {
-        let repo = self.borrow_repo()?;
+    pub fn git_update_submodules(&self, json_str: &str) -> Result<()> {
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-
-        let file_path = match json_hm.get("file_path") {
+        let path = match json_hm.get("path") {
             Some(s) => s,
-            None => bail!("file_path not returned from front-end payload."),
+            None => bail!("path not included in payload from front-end."),
         };
-        let change_type = match json_hm.get("change_type") {
-            Some(s) => s,
-            None => bail!("change_type not returned from front-end payload."),
+        let init = match json_hm.get("init") {
+            Some(s) => s == "true",
+            None => bail!("init not included in payload from front-end."),
         };
-        let sha = match json_hm.get("sha") {
-            Some(s) => s,
-            None => bail!("sha not returned from front-end payload."),
+        let recursive = match json_hm.get("recursive") {
+            Some(s) => s == "true",
+            None => bail!("recursive not included in payload from front-end."),
         };
 
-        let diff;
-        if change_type == "unstaged" {
-            diff = self.get_unstaged_changes()?;
-        } else if change_type == "staged" {
-            diff = self.get_staged_changes()?;
-        } else if change_type == "commit" {
-            let commit = repo.find_commit(Oid::from_str(sha)?)?;
-            diff = get_commit_changes(&commit, &repo)?;
-        } else {
-            bail!("change_type not a valid type. Needs to be 'staged', 'unstaged', or 'commit'");
-        }
-
-        let file_index = GitManager::get_file_index_in_diff(&diff, file_path.as_str())?;
-
-        let patch_opt = Patch::from_diff(&diff, file_index)?;
-        let mut file_lines = vec![];
-        let file_type = String::from(file_path.split(".").last().unwrap_or(""));
-        match patch_opt {
-            Some(mut patch) => {
-                patch.print(&mut |_diff_delta, _diff_hunk_opt, diff_line| {
-                    if diff_line.origin_value() == DiffLineType::FileHeader {
-                        if let Ok(s) = get_content_from_diff_line(&diff_line) {
-                            // Include file header if filemode has changed or the file was renamed.
-                            let is_filemode_change = !s.contains("+++") && (s.contains("old mode") || s.contains("new mode"));
-                            let is_renamed_file = s.contains("rename") || s.contains("similarity");
-                            if is_filemode_change || is_renamed_file {
-                                file_lines.push(LineInfo::SomeSeparator(s));
-                            }
-                        }
-                    } else if diff_line.origin_value() == DiffLineType::HunkHeader {
-                        if let Ok(s) = get_content_from_diff_line(&diff_line) {
-                            file_lines.push(LineInfo::SomeSeparator(s));
-                        }
-                    } else if let Ok(fli) = FileLineInfo::from_diff_line(diff_line, &file_type) {
-                        file_lines.push(LineInfo::SomeFileLineInfo(fli));
-                    }
-                    true
-                })?;
-            },
-            None => bail!("Patch not found in diff."),
-        }
-
-        let file_info = FileInfo::new(change_type.clone(), file_lines);
-        Ok(file_info)
+        self.update_submodules(self.borrow_repo()?, path, init, recursive)
     }
 
-    pub fn git_stage_all(&self) -> Result<()> {
-        let repo = self.borrow_repo()?;
+    fn sync_submodules(repo: &Repository, path: &str, recursive: bool) -> Result<()> {
+        for mut submodule in GitManager::get_selected_submodules(repo, path)? {
+            submodule.sync()?;
 
-        let mut index = repo.index()?;
-        index.add_all(&["."], IndexAddOption::DEFAULT, None)?;
-        index.write()?;
+            if recursive {
+                // Submodules that aren't checked out don't have anything of their own to sync.
+                if let Ok(submodule_repo) = submodule.open() {
+                    GitManager::sync_submodules(&submodule_repo, "", recursive)?;
+                }
+            }
+        }
 
         Ok(())
     }
 
-    fn git_commit(&self, full_message: String, author: &Signature, committer: &Signature, parent_commits: Vec<&Commit>) -> Result<()> {
-        if !self.has_staged_changes()? {
-            bail!("Attempted to commit with no staged changes! Maybe stage some changes first?");
-        }
+    pub fn git_sync_submodules(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let path = match json_hm.get("path") {
+            Some(s) => s,
+            None => bail!("path not included in payload from front-end."),
+        };
+        let recursive = match json_hm.get("recursive") {
+            Some(s) => s == "true",
+            None => bail!("recursive not included in payload from front-end."),
+        };
 
-        let repo = self.borrow_repo()?;
+        GitManager::sync_submodules(self.borrow_repo()?, path, recursive)
+    }
 
-        let mut index = repo.index()?;
-        let tree_oid = index.write_tree()?;
-        index.write()?;
-        let tree = repo.find_tree(tree_oid)?;
+    pub fn open_submodule(&mut self, json_str: &str) -> Result<()> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path: &str = GitManager::get_string_from_serde_string(path_value.as_str())?;
 
-        repo.commit(Some("HEAD"), author, committer, &*full_message, &tree, parent_commits.as_slice())?;
+        let submodule_repo = match self.borrow_repo()?.find_submodule(path)?.open() {
+            Ok(r) => r,
+            Err(_) => bail!("Submodule '{}' isn't checked out yet. Update it before opening it.", path),
+        };
+        self.repo = Some(submodule_repo);
 
         Ok(())
     }
 
-    pub fn git_commit_from_json(&self, json_string: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-        // TODO: Add way to set signature in git config
-        let signature = repo.signature()?;
-
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_string)?;
-        let summary = match json_hm.get("summaryText") {
-            Some(s) => s,
-            None => bail!("Front-end payload did not include summaryText"),
-        };
-        let message = match json_hm.get("messageText") {
-            Some(s) => s,
-            None => bail!("Front-end payload did not include messageText"),
-        };
-
-        let mut full_message = summary.clone();
-        if message != "" {
-            full_message += "\n\n";
-            full_message += message.as_str();
+    fn get_main_worktree_repo(repo: &Repository) -> Result<Repository> {
+        if !repo.is_worktree() {
+            return Ok(Repository::open(repo.path())?);
         }
+        // A linked worktree's git directory has a "commondir" file pointing back to the main repo's git directory.
+        let mut commondir_path = repo.path().to_path_buf();
+        commondir_path.push("commondir");
+        let mut common_path = repo.path().to_path_buf();
+        common_path.push(fs::read_to_string(commondir_path)?.trim());
+        Ok(Repository::open(common_path)?)
+    }
 
-        let mut parents = vec![];
-        match repo.head() {
+    fn get_worktree_head(worktree_repo: &Repository) -> Result<(Option<String>, Option<String>)> {
+        match worktree_repo.head() {
             Ok(head_ref) => {
-                if let Some(oid) = head_ref.target() {
-                    let commit = repo.find_commit(oid)?;
-                    parents.push(commit);
-                }
+                let head_branch = if head_ref.is_branch() {
+                    Some(String::from(GitManager::get_utf8_string(head_ref.name(), "Branch Name")?))
+                } else {
+                    None
+                };
+                Ok((head_branch, head_ref.target().map(|oid| oid.to_string())))
             },
             Err(e) => {
                 if e.code() != ErrorCode::UnbornBranch {
                     return Err(e.into());
                 }
+                Ok((None, None))
             },
-        };
-
-        // This is a hack for getting a slice of borrowed commits later.
-        let parent_refs: Vec<&Commit> = parents.iter().map(|c| {
-            c
-        }).collect();
+        }
+    }
 
-        self.git_commit(full_message, &signature, &signature, parent_refs)?;
-        Ok(())
+    fn get_workdir_string(worktree_repo: &Repository) -> Result<String> {
+        match worktree_repo.workdir() {
+            Some(p) => Ok(String::from(GitManager::get_utf8_string(fs::canonicalize(p)?.to_str(), "Worktree Path")?)),
+            None => bail!("Repository doesn't have a working directory."),
+        }
     }
 
-    pub fn git_discard_changes(&self, json_str: &str) -> Result<()> {
+    pub fn get_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
         let repo = self.borrow_repo()?;
+        let current_path = GitManager::get_workdir_string(repo)?;
+        let main_repo = GitManager::get_main_worktree_repo(repo)?;
+
+        let mut worktree_infos: Vec<WorktreeInfo> = vec![];
+        let main_path = GitManager::get_workdir_string(&main_repo)?;
+        let (head_branch, head_sha) = GitManager::get_worktree_head(&main_repo)?;
+        worktree_infos.push(WorktreeInfo {
+            name: None,
+            is_current: main_path == current_path,
+            path: main_path,
+            head_branch,
+            head_sha,
+            is_main: true,
+            is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
+        });
 
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let path = match json_hm.get("path") {
-            Some(s) => s,
-            None => bail!("path was not included in the payload from the front-end"),
-        };
-        let change_type = match json_hm.get("change_type") {
-            Some(s) => s,
-            None => bail!("change_type was not included in the payload from the front-end"),
-        };
-        let status: u8 = match json_hm.get("status") {
-            Some(s) => s.parse()?,
-            None => bail!("status was not included in the payload from the front-end"),
-        };
-
-        let mut cb = CheckoutBuilder::new();
-        cb.path(path);
-        cb.force();
-
-        if change_type == "unstaged" && status == 7 {  // if unstaged and untracked need to stage it to discard.
-            self.git_stage(status, path)?;
-        } else if status == 4 {  // if renamed, need to discard the new file and old file.
-            let diff;
-            if change_type == "unstaged" {
-                diff = self.get_unstaged_changes()?;
-            } else if change_type == "staged" {
-                diff = self.get_staged_changes()?;
-            } else {
-                bail!("Attempting to discard a renamed file that's neither staged nor unstaged. This error should technically be impossible.");
-            }
-
-            let diff_delta = match diff.get_delta(GitManager::get_file_index_in_diff(&diff, path.as_str())?) {
-                Some(dd) => dd,
-                None => bail!("Couldn't find DiffDelta associated with the renamed file, unable to discard changes."),
-            };
-
-            let old_path = match diff_delta.old_file().path() {
-                Some(p) => p,
-                None => bail!("Couldn't find old file path of renamed file, unable to discard changes."),
+        for worktree_name_opt in main_repo.worktrees()?.iter() {
+            let worktree_name = GitManager::get_utf8_string(worktree_name_opt, "Worktree Name")?;
+            let worktree = main_repo.find_worktree(worktree_name)?;
+            let (is_locked, lock_reason) = match worktree.is_locked()? {
+                WorktreeLockStatus::Unlocked => (false, None),
+                WorktreeLockStatus::Locked(reason_opt) => (true, reason_opt),
             };
-
-            cb.path(old_path);
+            let mut worktree_info = WorktreeInfo {
+                name: Some(String::from(worktree_name)),
+                path: String::from(GitManager::get_utf8_string(worktree.path().to_str(), "Worktree Path")?),
+                head_branch: None,
+                head_sha: None,
+                is_main: false,
+                is_current: false,
+                is_locked,
+                lock_reason,
+                is_prunable: worktree.validate().is_err(),
+            };
+            if !worktree_info.is_prunable {
+                // A worktree that can't be read is still listed, just without its head, instead of failing the whole list.
+                if let Ok(worktree_repo) = Repository::open_from_worktree(&worktree) {
+                    if let (Ok((head_branch, head_sha)), Ok(path)) = (GitManager::get_worktree_head(&worktree_repo), GitManager::get_workdir_string(&worktree_repo)) {
+                        worktree_info.path = path;
+                        worktree_info.is_current = worktree_info.path == current_path;
+                        worktree_info.head_branch = head_branch;
+                        worktree_info.head_sha = head_sha;
+                    }
+                }
+            }
+            worktree_infos.push(worktree_info);
         }
 
-        repo.checkout_head(Some(&mut cb))?;
-
-        Ok(())
+        Ok(worktree_infos)
     }
 
-    pub fn git_delete_local_branch(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
+    // Full branch names checked out in worktrees other than the open one, mapped to the worktree's path.
+    pub fn get_branch_worktree_paths(&self) -> Result<HashMap<String, String>> {
+        let mut branch_worktree_paths: HashMap<String, String> = HashMap::new();
+        for worktree_info in self.get_worktrees()? {
+            if worktree_info.is_current {
+                continue;
+            }
+            if let Some(head_branch) = worktree_info.head_branch {
+                branch_worktree_paths.insert(head_branch, worktree_info.path);
+            }
+        }
+        Ok(branch_worktree_paths)
+    }
 
+    pub fn git_add_worktree(&self, json_str: &str) -> Result<()> {
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let branch_shorthand = match json_hm.get("branch_shorthand") {
+        let name = match json_hm.get("name") {
             Some(s) => s,
-            None => bail!("branch_shorthand not included in payload from front-end!"),
+            None => bail!("name not included in payload from front-end."),
         };
-        let delete_remote_branch = match json_hm.get("delete_remote_branch") {
+        let path = match json_hm.get("path") {
+            Some(s) => s,
+            None => bail!("path not included in payload from front-end."),
+        };
+        let branch_name = match json_hm.get("branch_name") {
+            Some(s) => s,
+            None => bail!("branch_name not included in payload from front-end."),
+        };
+        let create_branch = match json_hm.get("create_branch") {
             Some(s) => s == "true",
-            None => bail!("delete_remote_branch not included in payload from front-end!"),
+            None => bail!("create_branch not included in payload from front-end."),
         };
 
-        let mut branch = repo.find_branch(branch_shorthand, BranchType::Local)?;
+        let repo = self.borrow_repo()?;
+        let branch = if create_branch {
+            let target_commit = match repo.head()?.target() {
+                Some(oid) => repo.find_commit(oid)?,
+                None => bail!("Current head not pointing at commit, cannot create branch."),
+            };
+            repo.branch(branch_name, &target_commit, false)?
+        } else {
+            let branch = repo.find_branch(branch_name, BranchType::Local)?;
+            let full_branch_name = GitManager::get_utf8_string(branch.get().name(), "Branch Name")?;
+            if branch.is_head() || self.get_branch_worktree_paths()?.contains_key(full_branch_name) {
+                bail!("Branch '{}' is already checked out in another worktree.", branch_name);
+            }
+            branch
+        };
 
-        if delete_remote_branch {
-            let remote_branch = branch.upstream()?;
-            self.git_delete_remote_branch(remote_branch)?;
-        }
+        let mut worktree_add_options = WorktreeAddOptions::new();
+        worktree_add_options.reference(Some(branch.get()));
+        repo.worktree(name, Path::new(path), Some(&worktree_add_options))?;
 
-        branch.delete()?;
         Ok(())
     }
 
-    fn git_delete_remote_branch(&self, remote_branch: Branch) -> Result<()> {
-        let repo = self.borrow_repo()?;
+    fn find_worktree_from_json(&self, json_str: &str) -> Result<Worktree> {
+        let name_value: Value = serde_json::from_str(json_str)?;
+        let name: &str = GitManager::get_string_from_serde_string(name_value.as_str())?;
+        let main_repo = GitManager::get_main_worktree_repo(self.borrow_repo()?)?;
+        Ok(main_repo.find_worktree(name)?)
+    }
 
-        let branch_shorthand = GitManager::get_utf8_string(remote_branch.get().shorthand(), "Branch Shorthand")?;
-        let remote_branch_full_name = GitManager::get_utf8_string(remote_branch.get().name(), "Branch Name")?;
+    pub fn git_lock_worktree(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let name = match json_hm.get("name") {
+            Some(s) => s,
+            None => bail!("name not included in payload from front-end."),
+        };
+        let reason = match json_hm.get("reason") {
+            Some(s) => s,
+            None => bail!("reason not included in payload from front-end."),
+        };
 
-        // Look for a local branch that already exists for the specified remote branch. If one exists,
-        // unset its upstream.
-        for local_b_result in repo.branches(Some(BranchType::Local))? {
-            let (mut local_b, _) = local_b_result?;
-            let local_upstream = match local_b.upstream() {
-                Ok(b) => b,
-                Err(_) => {
-                    continue;
-                },
-            };
-            let local_upstream_full_name = GitManager::get_utf8_string(local_upstream.get().name(), "Branch Name")?;
-            if local_upstream_full_name == remote_branch_full_name {
-                local_b.set_upstream(None)?;
-            }
+        let main_repo = GitManager::get_main_worktree_repo(self.borrow_repo()?)?;
+        let worktree = main_repo.find_worktree(name)?;
+        if reason == "" {
+            worktree.lock(None)?;
+        } else {
+            worktree.lock(Some(reason))?;
         }
 
-        let mut push_options = PushOptions::new();
-        push_options.remote_callbacks(GitManager::get_remote_callbacks());
-
-        let first_slash_index = match branch_shorthand.find("/") {
-            Some(i) => i,
-            None => bail!("Remote Branch doesn't seem to have a remote in its name?"),
-        };
-        let mut remote = repo.find_remote(&branch_shorthand[0..first_slash_index])?;
-        let refspec = String::from(":refs/heads/") + &branch_shorthand[(first_slash_index + 1)..];
-        remote.push(&[refspec.as_str()], Some(&mut push_options))?;
-        Ok(())
-    }
-
-    pub fn git_delete_remote_branch_from_json(&self, json_str: &str) -> Result<()> {
-        let branch_shorthand_value: Value = serde_json::from_str(json_str)?;
-        let branch_shorthand: &str = GitManager::get_string_from_serde_string(branch_shorthand_value.as_str())?;
-        let repo = self.borrow_repo()?;
-        let remote_branch = repo.find_branch(branch_shorthand, BranchType::Remote)?;
-        self.git_delete_remote_branch(remote_branch)?;
         Ok(())
     }
 
-    pub fn git_delete_tag(&self, json_str: &str) -> Result<()> {
-        let tag_name_value: Value = serde_json::from_str(json_str)?;
-        let tag_name: &str = GitManager::get_string_from_serde_string(tag_name_value.as_str())?;
-        let repo = self.borrow_repo()?;
-        repo.tag_delete(tag_name)?;
+    pub fn git_unlock_worktree(&self, json_str: &str) -> Result<()> {
+        self.find_worktree_from_json(json_str)?.unlock()?;
         Ok(())
     }
 
-    pub fn git_fetch(&self) -> Result<()> {
-        let repo = self.borrow_repo()?;
-        let remote_string_array = repo.remotes()?;
-        let empty_refspecs: &[String] = &[];
-        for remote_string_opt in remote_string_array.iter() {
-            let remote_string = GitManager::get_utf8_string(remote_string_opt, "Remote Name")?;
-            let mut remote = repo.find_remote(remote_string)?;
-            let mut fetch_options = FetchOptions::new();
-            fetch_options.download_tags(AutotagOption::All);
-            fetch_options.prune(FetchPrune::On);
-            fetch_options.remote_callbacks(GitManager::get_remote_callbacks());
-            remote.fetch(empty_refspecs, Some(&mut fetch_options), None)?;
+    pub fn git_prune_worktrees(&self) -> Result<()> {
+        let main_repo = GitManager::get_main_worktree_repo(self.borrow_repo()?)?;
+        // With the default options, only worktrees whose directories are gone and that aren't locked get pruned.
+        for worktree_name_opt in main_repo.worktrees()?.iter() {
+            let worktree = main_repo.find_worktree(GitManager::get_utf8_string(worktree_name_opt, "Worktree Name")?)?;
+            if worktree.is_prunable(None)? {
+                worktree.prune(None)?;
+            }
         }
         Ok(())
     }
 
-    pub fn git_fast_forward_branch(&self, json_str: &str) -> Result<()> {
+    pub fn git_remove_worktree(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let name = match json_hm.get("name") {
+            Some(s) => s,
+            None => bail!("name not included in payload from front-end."),
+        };
+        let force = match json_hm.get("force") {
+            Some(s) => s == "true",
+            None => bail!("force not included in payload from front-end."),
+        };
+
         let repo = self.borrow_repo()?;
+        let main_repo = GitManager::get_main_worktree_repo(repo)?;
+        let worktree = main_repo.find_worktree(name)?;
+        if worktree.validate().is_ok() {
+            let worktree_repo = Repository::open_from_worktree(&worktree)?;
+            if GitManager::get_workdir_string(&worktree_repo)? == GitManager::get_workdir_string(repo)? {
+                bail!("Can't remove the worktree that's currently open. Switch to a different worktree first.");
+            }
+            if !force {
+                let mut status_options = StatusOptions::new();
+                status_options.include_untracked(true);
+                if !worktree_repo.statuses(Some(&mut status_options))?.is_empty() {
+                    bail!("Worktree '{}' has changes that would be lost. Remove it with force to delete it anyway.", name);
+                }
+                if let WorktreeLockStatus::Locked(_) = worktree.is_locked()? {
+                    bail!("Worktree '{}' is locked. Unlock it or remove it with force.", name);
+                }
+            }
+        }
 
-        let branch_shorthand_value: Value = serde_json::from_str(json_str)?;
-        let branch_shorthand: &str = GitManager::get_string_from_serde_string(branch_shorthand_value.as_str())?;
+        let mut worktree_prune_options = WorktreePruneOptions::new();
+        worktree_prune_options.valid(true);
+        worktree_prune_options.locked(force);
+        worktree_prune_options.working_tree(true);
+        worktree.prune(Some(&mut worktree_prune_options))?;
 
-        // Fetch first to make sure everything's up to date.
-        self.git_fetch()?;
+        Ok(())
+    }
 
-        let mut local_branch = repo.find_branch(branch_shorthand, BranchType::Local)?;
+    pub fn switch_worktree(&mut self, json_str: &str) -> Result<()> {
+        let path_value: Value = serde_json::from_str(json_str)?;
+        let path: &str = GitManager::get_string_from_serde_string(path_value.as_str())?;
 
-        let remote_branch = local_branch.upstream()?;
-        let remote_ref = remote_branch.get();
-        let remote_target = match remote_ref.target() {
-            Some(oid) => oid,
-            None => bail!("Remote branch is not targeting a commit, cannot pull."),
+        let worktree_info = match self.get_worktrees()?.into_iter().find(|w| w.path == path) {
+            Some(w) => w,
+            None => bail!("'{}' isn't a worktree of the open repository.", path),
         };
-
-        let head = repo.head()?;
-        let local_ref = local_branch.get_mut();
-
-        // If fast-forwarding the branch currently checked out, need to update the working
-        // directory too.
-        if *local_ref == head {
-            let commit = match remote_ref.target() {
-                Some(oid) => repo.find_commit(oid)?,
-                None => bail!("Remote branch has no target commit."),
-            };
-            let tree = commit.tree()?;
-            repo.checkout_tree(tree.as_object(), None)?;
+        if worktree_info.is_prunable {
+            bail!("The worktree at '{}' no longer exists. Prune it to clean it up.", path);
         }
-
-        local_ref.set_target(remote_target, "oxidized_git fast-forward: setting new target for local ref")?;
+        self.repo = Some(Repository::open(Path::new(path))?);
 
         Ok(())
     }
 
-    pub fn git_pull(&self) -> Result<()> {
+    pub fn git_branch(&self, json_str: &str) -> Result<()> {
         let repo = self.borrow_repo()?;
 
-        // Fetch first to make sure everything's up to date.
-        self.git_fetch()?;
-
-        let mut local_ref = repo.head()?;
-        let local_shorthand = GitManager::get_utf8_string(local_ref.shorthand(), "Branch Name")?;
-        let local_branch = repo.find_branch(local_shorthand, BranchType::Local)?;
-
-        let remote_branch = local_branch.upstream()?;
-        let remote_ref = remote_branch.get();
-        let remote_target = match remote_ref.target() {
-            Some(oid) => oid,
-            None => bail!("Remote branch is not targeting a commit, cannot pull."),
+        let branch_options: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let branch_name = match branch_options.get("branch_name") {
+            Some(s) => s,
+            None => bail!("branch_name not included in payload from front-end."),
+        };
+        let checkout_on_create = match branch_options.get("checkout_on_create") {
+            Some(s) => s == "true",
+            None => bail!("checkout_on_create not included in payload from front-end."),
         };
-        let remote_ac = repo.find_annotated_commit(remote_target)?;
 
-        let (ma, mp) = repo.merge_analysis(&[&remote_ac])?;
+        // Older front-ends don't send this, so it's optional. It can be a sha, a tag, or a local or remote branch.
+        let start_point = match branch_options.get("start_point") {
+            Some(s) => s.as_str(),
+            None => "",
+        };
 
-        if ma.is_none() {
-            bail!("Merge analysis indicates no merge is possible. If you're reading this, your repository may be corrupted.");
-        } else if ma.is_unborn() {
-            bail!("The HEAD of the current repository is “unborn” and does not point to a valid commit. No pull can be performed, but the caller may wish to simply set HEAD to the target commit(s).");
-        } else if ma.is_up_to_date() {
-            return Ok(());
-        } else if ma.is_fast_forward() && !mp.is_no_fast_forward() {
-            println!("Performing fast forward merge for pull!");
-            let commit = match remote_ref.target() {
+        let new_branch = if start_point == "" {
+            let target_commit = match repo.head()?.target() {
                 Some(oid) => repo.find_commit(oid)?,
-                None => bail!("Remote branch has no target commit."),
-            };
-            let tree = commit.tree()?;
-            repo.checkout_tree(tree.as_object(), None)?;
-            local_ref.set_target(remote_target, "oxidized_git pull: setting new target for local ref")?;
-            return Ok(());
-        } else if ma.is_normal() && !mp.is_fastforward_only() {
-            println!("Performing rebase for pull!");
-            let mut rebase = repo.rebase(None, None, Some(&remote_ac), None)?;
-            let mut has_conflicts = false;
-            for step in rebase.by_ref() {
-                step?;
-                if self.has_conflicts()? || self.has_unstaged_changes()? || self.has_staged_changes()? {
-                    has_conflicts = true;
-                    break;
-                }
-            }
-            if has_conflicts {
-                rebase.abort()?;
-                bail!("Pull by rebase aborted because changes on local branch differ from remote branch!");
-            }
-            rebase.finish(None)?;
-            return Ok(());
-        } else if (ma.is_fast_forward() && mp.is_no_fast_forward()) || (ma.is_normal() && mp.is_fastforward_only()) {
-            bail!("It looks like a pull may be possible, but your MergePreference(s) are preventing it. If you have merge.ff or pull.ff set to 'only' or 'false', consider unsetting it by running 'git config --global --unset merge.ff' or 'git config --global --unset pull.ff'");
-        }
-        bail!("Merge analysis failed to make any determination on how to proceed with the pull. If you're reading this, your repository may be corrupted.")
-    }
-
-    pub fn git_push(&self, push_options_json_opt: Option<&str>) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let is_force;
-        let remote_name_from_frontend_opt;
-        let push_options: HashMap<String, String>;
-        if let Some(push_options_json) = push_options_json_opt {
-            push_options = serde_json::from_str(push_options_json)?;
-            is_force = match push_options.get("isForcePush") {
-                Some(s) => s == "true",
-                None => bail!("isForcePush not included in payload from front-end."),
-            };
-            remote_name_from_frontend_opt = match push_options.get("selectedRemote") {
-                Some(s) => Some(s.as_str()),
-                None => bail!("selectedRemote not included in payload from front-end."),
+                None => bail!("Current head not pointing at commit, cannot create branch."),
             };
+            repo.branch(branch_name, &target_commit, false)?
         } else {
-            is_force = false;
-            remote_name_from_frontend_opt = None;
-        }
-
-        let local_ref = repo.head()?;
-        let local_full_name = GitManager::get_utf8_string(local_ref.name(), "Branch Name")?;
-
-        let mut is_creating_new_remote_branch = false;
-        let mut remote = match repo.branch_upstream_remote(local_full_name) {
-            Ok(b) => {
-                let remote_name = GitManager::get_utf8_string(b.as_str(), "Remote Name")?;
-                repo.find_remote(remote_name)?
-            },
-            Err(_e) => {
-                is_creating_new_remote_branch = true;
-                match remote_name_from_frontend_opt {
-                    Some(rn) => repo.find_remote(rn)?,
-                    None => bail!("Attempted to push with no upstream branch and no specified remote."),
-                }
-            },
+            let (start_object, start_ref_opt) = repo.revparse_ext(start_point)?;
+            let target_commit = start_object.peel_to_commit()?;
+            let new_branch = repo.branch(branch_name, &target_commit, false)?;
+            if let Some(start_ref) = start_ref_opt {
+                GitManager::auto_setup_merge(repo, branch_name, &start_ref)?;
+            }
+            new_branch
         };
 
-        let mut push_options = PushOptions::new();
-        push_options.remote_callbacks(GitManager::get_remote_callbacks());
-
-        let mut sb = String::from(local_full_name);
-        if is_force {
-            sb.insert(0, '+');
-        }
-
-        remote.push(&[sb.as_str()], Some(&mut push_options))?;
-
-        if is_creating_new_remote_branch {
-            let local_branch_shorthand = GitManager::get_utf8_string(local_ref.shorthand(), "Branch Name")?;
-            let remote_name = GitManager::get_utf8_string(remote.name(), "Remote Name")?;
-            let new_remote_branch_shorthand = format!("{remote_name}/{local_branch_shorthand}");
-            let mut local_branch = repo.find_branch(local_branch_shorthand, BranchType::Local)?;
-            local_branch.set_upstream(Some(&*new_remote_branch_shorthand))?;
+        if checkout_on_create {
+            self.git_checkout(new_branch.get())?;
         }
 
         Ok(())
     }
 
-    pub fn git_push_tag(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let mut tag_full_name = match json_hm.get("tagFullName") {
-            Some(s) => s.clone(),
-            None => bail!("tagFullName not included in payload from front-end."),
-        };
-        let is_force = match json_hm.get("isForcePush") {
-            Some(s) => s == "true",
-            None => bail!("isForcePush not included in payload from front-end."),
+    // Sets up tracking for a new branch started from start_ref the way git does, according to branch.autoSetupMerge.
+    fn auto_setup_merge(repo: &Repository, branch_name: &str, start_ref: &Reference) -> Result<()> {
+        let auto_setup_merge = match repo.config()?.get_string("branch.autoSetupMerge") {
+            Ok(s) => s.to_lowercase(),
+            Err(e) => {
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+                String::from("true")
+            },
         };
-        let remote_name = match json_hm.get("selectedRemote") {
-            Some(s) => s.as_str(),
-            None => bail!("selectedRemote not included in payload from front-end."),
+
+        let start_shorthand = GitManager::get_utf8_string(start_ref.shorthand(), "Branch Name")?;
+        let upstream_shorthand_opt = if start_ref.is_remote() {
+            match auto_setup_merge.as_str() {
+                "true" | "always" => Some(String::from(start_shorthand)),
+                "simple" => {
+                    let remote_name = repo.branch_remote_name(GitManager::get_utf8_string(start_ref.name(), "Branch Name")?)?;
+                    let remote_name = GitManager::get_utf8_string(remote_name.as_str(), "Remote Name")?;
+                    if start_shorthand == format!("{}/{}", remote_name, branch_name) {
+                        Some(String::from(start_shorthand))
+                    } else {
+                        None
+                    }
+                },
+                "inherit" => GitManager::get_upstream_shorthand(repo, start_shorthand, BranchType::Remote)?,
+                _ => None,
+            }
+        } else if start_ref.is_branch() {
+            match auto_setup_merge.as_str() {
+                "always" => Some(String::from(start_shorthand)),
+                "inherit" => GitManager::get_upstream_shorthand(repo, start_shorthand, BranchType::Local)?,
+                _ => None,
+            }
+        } else {
+            None
         };
 
-        let mut remote = repo.find_remote(remote_name)?;
-
-        let mut push_options = PushOptions::new();
-        push_options.remote_callbacks(GitManager::get_remote_callbacks());
-
-        if is_force {
-            tag_full_name.insert(0, '+');
+        if let Some(upstream_shorthand) = upstream_shorthand_opt {
+            repo.find_branch(branch_name, BranchType::Local)?.set_upstream(Some(&upstream_shorthand))?;
         }
 
-        remote.push(&[tag_full_name.as_str()], Some(&mut push_options))?;
-
         Ok(())
     }
 
-    pub fn git_stash(&mut self, json_str: &str) -> Result<()> {
-        let message_value: Value = serde_json::from_str(json_str)?;
-        let message: &str = GitManager::get_string_from_serde_string(message_value.as_str())?;
-        let repo = self.borrow_repo_mut()?;
-
-        if message == "" {
-            repo.stash_save2(&repo.signature()?, None, Some(StashFlags::INCLUDE_UNTRACKED))?;
-        } else {
-            repo.stash_save2(&repo.signature()?, Some(message), Some(StashFlags::INCLUDE_UNTRACKED))?;
+    fn get_upstream_shorthand(repo: &Repository, branch_shorthand: &str, branch_type: BranchType) -> Result<Option<String>> {
+        // Remote-tracking branches don't have upstreams of their own.
+        if branch_type == BranchType::Remote {
+            return Ok(None);
+        }
+        match repo.find_branch(branch_shorthand, branch_type)?.upstream() {
+            Ok(b) => Ok(Some(String::from(GitManager::get_utf8_string(b.get().shorthand(), "Branch Name")?))),
+            Err(e) => {
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+                Ok(None)
+            },
         }
-
-        Ok(())
     }
 
-    pub fn git_apply_stash(&mut self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo_mut()?;
-
+    pub fn git_rename_branch(&self, json_str: &str) -> Result<()> {
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let index = match json_hm.get("index") {
-            Some(s) => {
-                s.parse::<usize>()?
-            },
-            None => bail!("index not included in payload from front-end."),
+        let branch_name = match json_hm.get("branch_name") {
+            Some(s) => s,
+            None => bail!("branch_name not included in payload from front-end."),
         };
-        let delete_stash = match json_hm.get("delete_stash") {
+        let new_branch_name = match json_hm.get("new_branch_name") {
+            Some(s) => s,
+            None => bail!("new_branch_name not included in payload from front-end."),
+        };
+        let rename_remote = match json_hm.get("rename_remote") {
             Some(s) => s == "true",
-            None => bail!("delete_stash not included in payload from front-end."),
+            None => bail!("rename_remote not included in payload from front-end."),
         };
 
-        if delete_stash {
-            repo.stash_pop(index, None)?;
-        } else {
-            repo.stash_apply(index, None)?;
+        let repo = self.borrow_repo()?;
+        let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+        let full_branch_name = GitManager::get_utf8_string(branch.get().name(), "Branch Name")?;
+        if let Some(worktree_path) = self.get_branch_worktree_paths()?.get(full_branch_name) {
+            bail!("Branch '{}' is checked out in the worktree at '{}', so it can't be renamed from here.", branch_name, worktree_path);
         }
 
-        Ok(())
-    }
+        let old_upstream_opt = match branch.upstream() {
+            Ok(b) => Some(b),
+            Err(e) => {
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+                None
+            },
+        };
+        if rename_remote && old_upstream_opt.is_none() {
+            bail!("Branch '{}' doesn't have an upstream branch to rename.", branch_name);
+        }
 
-    pub fn git_delete_stash(&mut self, json_str: &str) -> Result<()> {
-        let stash_index_str_value: Value = serde_json::from_str(json_str)?;
-        let stash_index_str: &str = GitManager::get_string_from_serde_string(stash_index_str_value.as_str())?;
-        let repo = self.borrow_repo_mut()?;
+        if !Branch::name_is_valid(new_branch_name)? {
+            bail!("'{}' is not a valid branch name.", new_branch_name);
+        }
+        if repo.find_branch(new_branch_name, BranchType::Local).is_ok() {
+            bail!("A branch named '{}' already exists.", new_branch_name);
+        }
 
-        let index = stash_index_str.parse::<usize>()?;
+        // The new remote branch is pushed before anything changes locally, so a failed push leaves everything as it was.
+        let mut old_upstream_and_remote_name_opt = None;
+        if rename_remote {
+            if let Some(old_upstream) = old_upstream_opt {
+                let old_upstream_full_name = GitManager::get_utf8_string(old_upstream.get().name(), "Branch Name")?;
+                let remote_name_buf = repo.branch_remote_name(old_upstream_full_name)?;
+                let remote_name = String::from(GitManager::get_utf8_string(remote_name_buf.as_str(), "Remote Name")?);
+                let mut remote = repo.find_remote(&remote_name)?;
+
+                let credential_session = self.new_credential_session(Some(repo), GitManager::get_remote_push_url(&remote));
+                let mut push_options = PushOptions::new();
+                push_options.remote_callbacks(GitManager::get_remote_callbacks(&credential_session));
+                let refspec = format!("{}:refs/heads/{}", full_branch_name, new_branch_name);
+                credential_session.finish(remote.push(&[refspec.as_str()], Some(&mut push_options)))?;
+                old_upstream_and_remote_name_opt = Some((old_upstream, remote_name));
+            }
+        }
 
-        repo.stash_drop(index)?;
+        // This also moves the branch's config and updates HEAD if the branch is checked out.
+        let mut new_branch = branch.rename(new_branch_name, false)?;
+
+        if let Some((old_upstream, remote_name)) = old_upstream_and_remote_name_opt {
+            // Track the new remote branch first so deleting the old one doesn't unset this branch's upstream.
+            new_branch.set_upstream(Some(&*format!("{}/{}", remote_name, new_branch_name)))?;
+            self.git_delete_remote_branch(old_upstream)?;
+        }
 
         Ok(())
     }
 
-    pub fn git_branch(&self, json_str: &str) -> Result<()> {
-        let repo = self.borrow_repo()?;
-
-        let branch_options: HashMap<String, String> = serde_json::from_str(json_str)?;
-        let branch_name = match branch_options.get("branch_name") {
+    pub fn git_set_upstream(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let branch_name = match json_hm.get("branch_name") {
             Some(s) => s,
             None => bail!("branch_name not included in payload from front-end."),
         };
-        let checkout_on_create = match branch_options.get("checkout_on_create") {
-            Some(s) => s == "true",
-            None => bail!("checkout_on_create not included in payload from front-end."),
-        };
-
-        let target_commit = match repo.head()?.target() {
-            Some(oid) => repo.find_commit(oid)?,
-            None => bail!("Current head not pointing at commit, cannot create branch."),
+        let upstream_branch_name = match json_hm.get("upstream_branch_name") {
+            Some(s) => s,
+            None => bail!("upstream_branch_name not included in payload from front-end."),
         };
 
-        let new_branch = repo.branch(branch_name, &target_commit, false)?;
-
-        if checkout_on_create {
-            self.git_checkout(new_branch.get())?;
+        let repo = self.borrow_repo()?;
+        let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+        if upstream_branch_name == branch_name {
+            bail!("A branch can't be its own upstream.");
         }
+        branch.set_upstream(Some(upstream_branch_name))?;
+
+        Ok(())
+    }
 
+    pub fn git_unset_upstream(&self, json_str: &str) -> Result<()> {
+        let branch_name_value: Value = serde_json::from_str(json_str)?;
+        let branch_name: &str = GitManager::get_string_from_serde_string(branch_name_value.as_str())?;
+        let repo = self.borrow_repo()?;
+        let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+        let full_branch_name = GitManager::get_utf8_string(branch.get().name(), "Branch Name")?;
+        match repo.branch_upstream_name(full_branch_name) {
+            Ok(_) => branch.set_upstream(None)?,
+            Err(e) => {
+                // libgit2 errors when unsetting an upstream that isn't there, but that's already the desired state.
+                if e.code() != ErrorCode::NotFound {
+                    return Err(e.into());
+                }
+            },
+        };
         Ok(())
     }
 
@@ -1519,79 +5507,182 @@ impl GitManager {
         if is_lightweight {
             repo.tag_lightweight(name, &git_object, false)?;
         } else {
-            let sig = repo.signature()?;
-            repo.tag(name, &git_object, &sig, message, false)?;
+            let sig = self.get_signature()?;
+            let config = config_manager::get_config()?;
+            if self.should_sign("tag.gpgsign", config.borrow_sign_tags())? {
+                self.git_signed_tag(name, &git_object, &sig, message)?;
+            } else {
+                repo.tag(name, &git_object, &sig, message, false)?;
+            }
         }
 
         Ok(())
     }
 
-    #[allow(unused_unsafe)]
-    fn get_remote_callbacks() -> RemoteCallbacks<'static> {
-        let mut callbacks = RemoteCallbacks::new();
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            let config = match config_manager::get_config() {
-                Ok(c) => c,
-                Err(e) => return Err(git2::Error::from_str(&*format!("Error during config file read: {}", e))),
+    // On top of the usual callbacks, these report progress if there's a handler for it and abort when cancelled.
+    fn get_remote_callbacks_with_progress(&self, credential_session: &CredentialSession, operation: &str, remote_name: &str) -> RemoteCallbacks<'static> {
+        let mut callbacks = GitManager::get_remote_callbacks(credential_session);
+        let remote_progress_handler_opt = self.remote_progress_handler_opt.clone();
+
+        let handler_opt = remote_progress_handler_opt.clone();
+        let cancel_handle = self.cancel_handle.clone();
+        let (operation_c, remote_name_c) = (String::from(operation), String::from(remote_name));
+        let mut last_emit_opt: Option<Instant> = None;
+        callbacks.transfer_progress(move |progress| {
+            if let Some(handler) = &handler_opt {
+                let is_received = progress.received_objects() == progress.total_objects();
+                let is_done = is_received && progress.indexed_deltas() == progress.total_deltas();
+                if GitManager::should_emit_progress(&mut last_emit_opt, is_done) {
+                    if !is_received || progress.total_deltas() == 0 {
+                        handler(RemoteProgress::new(&operation_c, "receiving", &remote_name_c, progress.received_objects(), progress.total_objects(), progress.received_bytes(), None));
+                    } else {
+                        handler(RemoteProgress::new(&operation_c, "resolving", &remote_name_c, progress.indexed_deltas(), progress.total_deltas(), progress.received_bytes(), None));
+                    }
+                }
+            }
+            !cancel_handle.is_cancelled()
+        });
+
+        let handler_opt = remote_progress_handler_opt.clone();
+        let cancel_handle = self.cancel_handle.clone();
+        let (operation_c, remote_name_c) = (String::from(operation), String::from(remote_name));
+        let mut last_emit_opt: Option<Instant> = None;
+        callbacks.sideband_progress(move |data| {
+            if let Some(handler) = &handler_opt {
+                let message = String::from_utf8_lossy(data).trim().to_string();
+                // Servers end a step's last progress line with a newline instead of a carriage return.
+                let is_step_done = data.ends_with(b"\n");
+                if message != "" && GitManager::should_emit_progress(&mut last_emit_opt, is_step_done) {
+                    handler(RemoteProgress::new(&operation_c, "remote", &remote_name_c, 0, 0, 0, Some(message)));
+                }
+            }
+            !cancel_handle.is_cancelled()
+        });
+
+        // Pushes can't be aborted once the pack is being uploaded, so this is the last chance to cancel one.
+        let cancel_handle = self.cancel_handle.clone();
+        callbacks.push_negotiation(move |_updates| {
+            if cancel_handle.is_cancelled() {
+                return Err(git2::Error::from_str("The operation was cancelled."));
+            }
+            Ok(())
+        });
+
+        let remote_progress_handler = match remote_progress_handler_opt {
+            Some(h) => h,
+            None => return callbacks,
+        };
+
+        let handler = remote_progress_handler.clone();
+        let (operation_c, remote_name_c) = (String::from(operation), String::from(remote_name));
+        let mut last_emit_opt: Option<Instant> = None;
+        callbacks.pack_progress(move |stage, current, total| {
+            let phase = match stage {
+                PackBuilderStage::AddingObjects => "counting",
+                PackBuilderStage::Deltafication => "compressing",
             };
-            let cred_type = match config.borrow_cred_type() {
-                Some(s) => s.clone(),
+            if GitManager::should_emit_progress(&mut last_emit_opt, current == total) {
+                handler(RemoteProgress::new(&operation_c, phase, &remote_name_c, current, total, 0, None));
+            }
+        });
+
+        let handler = remote_progress_handler;
+        let (operation_c, remote_name_c) = (String::from(operation), String::from(remote_name));
+        let mut last_emit_opt: Option<Instant> = None;
+        callbacks.push_transfer_progress(move |current, total, bytes| {
+            if GitManager::should_emit_progress(&mut last_emit_opt, current == total) {
+                handler(RemoteProgress::new(&operation_c, "uploading", &remote_name_c, current, total, bytes, None));
+            }
+        });
+
+        callbacks
+    }
+
+    // Final updates always go through, everything else at most once per REMOTE_PROGRESS_INTERVAL.
+    fn should_emit_progress(last_emit_opt: &mut Option<Instant>, is_final: bool) -> bool {
+        let should_emit = is_final || last_emit_opt.map_or(true, |t| t.elapsed() >= REMOTE_PROGRESS_INTERVAL);
+        if should_emit {
+            *last_emit_opt = Some(Instant::now());
+        }
+        should_emit
+    }
+
+    #[allow(unused_unsafe)]
+    fn get_stored_cred(url: &str, username_from_url: Option<&str>) -> Result<Cred, git2::Error> {
+        let config = match config_manager::get_config() {
+            Ok(c) => c,
+            Err(e) => return Err(git2::Error::from_str(&*format!("Error during config file read: {}", e))),
+        };
+        let profile = match config.get_credential_profile_for_url(url) {
+            Some(p) => p,
+            None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
+        };
+        let cred_type = profile.borrow_cred_type();
+        if cred_type == "HTTPS" {
+            let username = match profile.borrow_https_username() {
+                Some(u) => u.clone(),
                 None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
             };
-            return if cred_type == "HTTPS" {
-                let username = match config.borrow_https_username() {
-                    Some(u) => u.clone(),
-                    None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
+            let pass;
+            unsafe {
+                pass = match keytar::get_password("oxidized_git", &*profile.get_keychain_account("password")) {
+                    Ok(p) => p,
+                    Err(_) => return Err(git2::Error::from_str("Error finding password in keychain!")),
                 };
+            }
+            if pass.success {
+                Cred::userpass_plaintext(username.as_str(), &*pass.password)
+            } else {
+                Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials"))
+            }
+        } else if cred_type == "SSH" {
+            let username = match username_from_url {
+                Some(s) => s,
+                None => return Err(git2::Error::from_str("No username in Remote URL, did you use an SSH URL for your remote?")),
+            };
+            let public_key_path = match profile.borrow_public_key_path() {
+                Some(p) => p,
+                None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
+            };
+            let private_key_path = match profile.borrow_private_key_path() {
+                Some(p) => p,
+                None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
+            };
+            let uses_passphrase = match profile.borrow_uses_passphrase() {
+                Some(b) => b,
+                None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
+            };
+
+            if *uses_passphrase {
                 let pass;
                 unsafe {
-                    pass = match keytar::get_password("oxidized_git", "password") {
+                    pass = match keytar::get_password("oxidized_git", &*profile.get_keychain_account("passphrase")) {
                         Ok(p) => p,
-                        Err(_) => return Err(git2::Error::from_str("Error finding password in keychain!")),
+                        Err(_) => return Err(git2::Error::from_str("Error finding passphrase in keychain!")),
                     };
                 }
                 if pass.success {
-                    Cred::userpass_plaintext(username.as_str(), &*pass.password)
+                    Cred::ssh_key(username, Some(public_key_path), private_key_path, Some(&*pass.password))
                 } else {
                     Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials"))
                 }
-            } else if cred_type == "SSH" {
-                let username = match username_from_url {
-                    Some(s) => s,
-                    None => return Err(git2::Error::from_str("No username in Remote URL, did you use an SSH URL for your remote?")),
-                };
-                let public_key_path = match config.borrow_public_key_path() {
-                    Some(p) => p,
-                    None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
-                };
-                let private_key_path = match config.borrow_private_key_path() {
-                    Some(p) => p,
-                    None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
-                };
-                let uses_passphrase = match config.borrow_uses_passphrase() {
-                    Some(b) => b,
-                    None => return Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials")),
-                };
-
-                if *uses_passphrase {
-                    let pass;
-                    unsafe {
-                        pass = match keytar::get_password("oxidized_git", "passphrase") {
-                            Ok(p) => p,
-                            Err(_) => return Err(git2::Error::from_str("Error finding passphrase in keychain!")),
-                        };
-                    }
-                    if pass.success {
-                        Cred::ssh_key(username, Some(public_key_path), private_key_path, Some(&*pass.password))
-                    } else {
-                        Err(git2::Error::from_str("Credentials are required to perform that operation. Please set your credentials in the menu bar under Security > Set Credentials"))
-                    }
-                } else {
-                    Cred::ssh_key(username, Some(public_key_path), private_key_path, None)
-                }
             } else {
-                Err(git2::Error::from_str("Credential Type unrecognized. Please set your credentials in the menu bar under Security > Set Credentials"))
+                Cred::ssh_key(username, Some(public_key_path), private_key_path, None)
             }
+        } else {
+            Err(git2::Error::from_str("Credential Type unrecognized. Please set your credentials in the menu bar under Security > Set Credentials"))
+        }
+    }
+
+    fn get_remote_callbacks(credential_session: &CredentialSession) -> RemoteCallbacks<'static> {
+        let mut callbacks = RemoteCallbacks::new();
+        let credential_session_c = credential_session.clone();
+        callbacks.credentials(move |url, username_from_url, allowed_types| {
+            credential_session_c.get_next_cred(url, username_from_url, allowed_types)
+        });
+        let credential_session_c = credential_session.clone();
+        callbacks.certificate_check(move |cert, hostname| {
+            credential_session_c.check_certificate(cert, hostname)
         });
         callbacks.push_update_reference(|_ref_name, status_msg| {
             match status_msg {
@@ -1602,6 +5693,41 @@ impl GitManager {
         callbacks
     }
 
+    pub fn accept_host_key(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let hostname = match json_hm.get("hostname") {
+            Some(s) => s.clone(),
+            None => bail!("hostname not included in payload from front-end."),
+        };
+        let key_type = match json_hm.get("key_type") {
+            Some(s) => s.clone(),
+            None => bail!("key_type not included in payload from front-end."),
+        };
+        let key = match json_hm.get("key") {
+            Some(s) => s.clone(),
+            None => bail!("key not included in payload from front-end."),
+        };
+        let persist = match json_hm.get("persist") {
+            Some(s) => s == "true",
+            None => bail!("persist not included in payload from front-end."),
+        };
+
+        let known_host_key = KnownHostKey {
+            hostname,
+            key_type,
+            key,
+        };
+        if persist {
+            known_host_key.add_to_known_hosts()?;
+        } else {
+            let mut session_host_keys = self.session_host_keys.lock().unwrap();
+            if !session_host_keys.contains(&known_host_key) {
+                session_host_keys.push(known_host_key);
+            }
+        }
+        Ok(())
+    }
+
     #[allow(unused_unsafe)]
     pub fn set_https_credentials(&self, json_str: &str) -> Result<()> {
         let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
@@ -1660,4 +5786,172 @@ impl GitManager {
 
         Ok(())
     }
+
+    #[allow(unused_unsafe)]
+    pub fn save_credential_profile(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let profile_name = match json_hm.get("profile_name") {
+            Some(s) => s.clone(),
+            None => bail!("No profile_name supplied from front-end."),
+        };
+        let url_pattern = match json_hm.get("url_pattern") {
+            Some(s) => s.clone(),
+            None => bail!("No url_pattern supplied from front-end."),
+        };
+        let cred_type = match json_hm.get("cred_type") {
+            Some(s) => s.clone(),
+            None => bail!("No cred_type supplied from front-end."),
+        };
+
+        if profile_name == "" || url_pattern == "" {
+            bail!("A credential profile needs a profile name and a host or URL to match.");
+        }
+
+        let mut profile = CredentialProfile::new(profile_name, url_pattern, cred_type.clone());
+        if cred_type == "HTTPS" {
+            let username = match json_hm.get("username") {
+                Some(s) => s.clone(),
+                None => bail!("No username supplied from front-end."),
+            };
+            let password = match json_hm.get("password") {
+                Some(s) => s,
+                None => bail!("No password supplied from front-end."),
+            };
+            profile.set_https_username(username);
+            unsafe {
+                keytar::set_password("oxidized_git", &*profile.get_keychain_account("password"), password)?;
+            }
+        } else if cred_type == "SSH" {
+            let public_key_path = match json_hm.get("public_key_path") {
+                Some(s) => s.clone(),
+                None => bail!("No public_key_path supplied from front-end."),
+            };
+            let private_key_path = match json_hm.get("private_key_path") {
+                Some(s) => s.clone(),
+                None => bail!("No private_key_path supplied from front-end."),
+            };
+            let passphrase = match json_hm.get("passphrase") {
+                Some(s) => s.clone(),
+                None => bail!("No passphrase supplied from front-end."),
+            };
+            profile.set_public_key_path(public_key_path.into());
+            profile.set_private_key_path(private_key_path.into());
+            if passphrase != "" {
+                profile.set_uses_passphrase(true);
+                unsafe {
+                    keytar::set_password("oxidized_git", &*profile.get_keychain_account("passphrase"), &*passphrase)?;
+                }
+            } else {
+                profile.set_uses_passphrase(false);
+            }
+        } else {
+            bail!("Credential Type unrecognized: {}", cred_type);
+        }
+
+        let mut config = config_manager::get_config()?;
+        config.set_credential_profile(profile);
+        config.save()?;
+
+        Ok(())
+    }
+
+    pub fn get_credential_profiles(&self) -> Result<Vec<CredentialProfile>> {
+        let config = config_manager::get_config()?;
+        Ok(config.borrow_credential_profiles().clone().unwrap_or_default())
+    }
+
+    #[allow(unused_unsafe)]
+    pub fn delete_credential_profile(&self, json_str: &str) -> Result<()> {
+        let profile_name_value: Value = serde_json::from_str(json_str)?;
+        let profile_name: &str = GitManager::get_string_from_serde_string(profile_name_value.as_str())?;
+
+        let mut config = config_manager::get_config()?;
+        if let Some(profile) = config.get_credential_profile(profile_name) {
+            // Deleting a secret that was never stored isn't a problem, so the results are ignored.
+            unsafe {
+                let _ = keytar::delete_password("oxidized_git", &*profile.get_keychain_account("password"));
+                let _ = keytar::delete_password("oxidized_git", &*profile.get_keychain_account("passphrase"));
+            }
+        }
+        config.remove_credential_profile(profile_name);
+        config.save()?;
+
+        Ok(())
+    }
+
+    pub fn save_identity_profile(&self, json_str: &str) -> Result<()> {
+        let json_hm: HashMap<String, String> = serde_json::from_str(json_str)?;
+        let profile_name = match json_hm.get("profile_name") {
+            Some(s) => s.clone(),
+            None => bail!("No profile_name supplied from front-end."),
+        };
+        let name = match json_hm.get("name") {
+            Some(s) => s.clone(),
+            None => bail!("No name supplied from front-end."),
+        };
+        let email = match json_hm.get("email") {
+            Some(s) => s.clone(),
+            None => bail!("No email supplied from front-end."),
+        };
+        let is_default = match json_hm.get("is_default") {
+            Some(s) => s == "true",
+            None => bail!("No is_default supplied from front-end."),
+        };
+
+        if profile_name == "" || name == "" || email == "" {
+            bail!("An identity profile needs a profile name, a name, and an email.");
+        }
+
+        let mut config = config_manager::get_config()?;
+        config.set_identity_profile(IdentityProfile::new(profile_name.clone(), name, email));
+        if is_default {
+            config.set_default_identity_profile(Some(profile_name));
+        } else if config.borrow_default_identity_profile().as_ref() == Some(&profile_name) {
+            config.set_default_identity_profile(None);
+        }
+        config.save()?;
+
+        Ok(())
+    }
+
+    pub fn delete_identity_profile(&self, json_str: &str) -> Result<()> {
+        let profile_name_value: Value = serde_json::from_str(json_str)?;
+        let profile_name: &str = GitManager::get_string_from_serde_string(profile_name_value.as_str())?;
+
+        let mut config = config_manager::get_config()?;
+        config.remove_identity_profile(profile_name);
+        config.save()?;
+
+        Ok(())
+    }
+
+    pub fn set_repo_identity(&self, json_str: &str) -> Result<()> {
+        let profile_name_value: Value = serde_json::from_str(json_str)?;
+        let profile_name: &str = GitManager::get_string_from_serde_string(profile_name_value.as_str())?;
+        let repo = self.borrow_repo()?;
+
+        let mut local_config = repo.config()?.open_level(ConfigLevel::Local)?;
+
+        // An empty profile name clears the repo specific identity so the default one is used again.
+        if profile_name == "" {
+            for key in ["user.name", "user.email"] {
+                if let Err(e) = local_config.remove(key) {
+                    if e.code() != ErrorCode::NotFound {
+                        return Err(e.into());
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let config = config_manager::get_config()?;
+        let profile = match config.get_identity_profile(profile_name) {
+            Some(p) => p,
+            None => bail!("Identity profile '{}' doesn't exist.", profile_name),
+        };
+        local_config.set_str("user.name", profile.borrow_name())?;
+        local_config.set_str("user.email", profile.borrow_email())?;
+
+        Ok(())
+    }
 }
@@ -8,15 +8,23 @@ pub mod config_manager;
 pub mod svg_row;
 pub mod parseable_info;
 
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, MutexGuard};
 use std::thread;
 use tauri::{CustomMenuItem, Manager, Menu, MenuItem, Submenu, Window, WindowBuilder, WindowEvent, Wry};
 use tauri::MenuEntry::NativeItem;
 use time::UtcOffset;
-use git_manager::GitManager;
-use parseable_info::{get_parseable_repo_info, get_files_changed_info_list};
+use git_manager::{GitManager, OperationCancelled, SharedGitManager, UnknownHostKey};
+use parseable_info::{get_parseable_repo_info, get_files_changed_info_list, get_next_commit_graph_page};
 
 fn handle_error(e: anyhow::Error, main_window: &Window<Wry>) {
+    if e.is::<OperationCancelled>() {
+        main_window.emit_all("cancelled", "").unwrap();
+        return;
+    }
+    if let Some(unknown_host_key) = e.downcast_ref::<UnknownHostKey>() {
+        main_window.emit_all("unknown-host-key", unknown_host_key.clone()).unwrap();
+        return;
+    }
     let error_string = format!("{:?}", e);
     main_window.emit_all("error", error_string).unwrap();
 }
@@ -121,7 +129,18 @@ fn main() {
         .title("Oxidized Git")
         .build()?;
 
-        let git_manager_arc: Arc<Mutex<GitManager>> = Arc::new(Mutex::new(GitManager::new(current_local_offset)));
+        let mut git_manager = GitManager::new(current_local_offset);
+        let main_window_c = main_window.clone();
+        git_manager.set_remote_progress_handler(move |remote_progress| {
+            main_window_c.emit_all("remote-progress", remote_progress).unwrap();
+        });
+        let cancel_handle = git_manager.get_cancel_handle();
+        let git_manager_arc: Arc<SharedGitManager> = Arc::new(SharedGitManager::new(git_manager));
+
+        // This doesn't lock the GitManager, since the operation being cancelled is holding it.
+        main_window.listen("cancel-operation", move |_event| {
+            cancel_handle.cancel();
+        });
 
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
@@ -201,6 +220,72 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("load-more-commits", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = get_next_commit_graph_page(&mut git_manager);
+                match result {
+                    Ok(commit_graph_page) => main_window_c_c.emit_all("commit-graph-page", commit_graph_page).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-graph-scope", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_graph_scope();
+                match result {
+                    Ok(graph_scope) => main_window_c_c.emit_all("graph-scope", graph_scope).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("set-graph-scope", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.set_graph_scope(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, true, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("search-commits", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.search_commits(s);
+                        match result {
+                            Ok(commit_search_results) => main_window_c_c.emit_all("commit-search-results", commit_search_results).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
         main_window.listen("init", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
@@ -296,6 +381,44 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-rebase-todo-list", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_rebase_todo_list(s);
+                        match result {
+                            Ok(todo_list) => main_window_c_c.emit_all("rebase-todo-list", todo_list).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("interactive-rebase", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_interactive_rebase(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
         main_window.listen("cherrypick", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
@@ -487,213 +610,161 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("stage", move |event| {
-            let main_window_c_c = main_window_c.clone();
-            let git_manager_arc_c_c = git_manager_arc_c.clone();
-            thread::spawn(move || {
-                match event.payload() {
-                    Some(s) => {
-                        let git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_stage_from_json(s);
-                        match result {
-                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
-                            Err(e) => handle_error(e, &main_window_c_c),
-                        };
-                    },
-                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
-            });
-        });
-        let main_window_c = main_window.clone();
-        let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("unstage", move |event| {
+        main_window.listen("save-credential-profile", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_unstage(s);
+                        let result = git_manager.save_credential_profile(s);
                         match result {
-                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                            Ok(()) => (),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
+                };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("file-diff", move |event| {
+        main_window.listen("delete-credential-profile", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.get_file_diff(s);
+                        let result = git_manager.delete_credential_profile(s);
                         match result {
-                            Ok(file_lines) => main_window_c_c.emit_all("show-file-lines", file_lines).unwrap(),
+                            Ok(()) => (),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
+                };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("stage-all", move |_event| {
+        main_window.listen("get-credential-profiles", move |_event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 let git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_stage_all();
+                let result = git_manager.get_credential_profiles();
                 match result {
-                    Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                    Ok(credential_profiles) => main_window_c_c.emit_all("show-credential-profiles", credential_profiles).unwrap(),
                     Err(e) => handle_error(e, &main_window_c_c),
                 };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("commit", move |event| {
+        main_window.listen("accept-host-key", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
-                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_commit_from_json(s);
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.accept_host_key(s);
                         match result {
-                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Ok(()) => (),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
+                };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("commit-push", move |event| {
+        main_window.listen("save-identity-profile", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
-                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_commit_from_json(s);
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.save_identity_profile(s);
                         match result {
-                            Ok(()) => {
-                                let result_2 = git_manager.git_push(None);
-                                match result_2 {
-                                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                                    Err(e) => handle_error(e, &main_window_c_c),
-                                };
-                            },
+                            Ok(()) => (),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
-            });
-        });
-        let main_window_c = main_window.clone();
-        let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("abort", move |_event| {
-            let main_window_c_c = main_window_c.clone();
-            let git_manager_arc_c_c = git_manager_arc_c.clone();
-            thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_abort();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
-                };
-            });
-        });
-        let main_window_c = main_window.clone();
-        let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("continue-cherrypick", move |_event| {
-            let main_window_c_c = main_window_c.clone();
-            let git_manager_arc_c_c = git_manager_arc_c.clone();
-            thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_continue_cherrypick();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
                 };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("continue-revert", move |_event| {
-            let main_window_c_c = main_window_c.clone();
-            let git_manager_arc_c_c = git_manager_arc_c.clone();
-            thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_continue_revert();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
-                };
-            });
-        });
-        let main_window_c = main_window.clone();
-        let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("continue-merge", move |_event| {
+        main_window.listen("delete-identity-profile", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_continue_merge();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.delete_identity_profile(s);
+                        match result {
+                            Ok(()) => (),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
                 };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("abort-rebase", move |_event| {
+        main_window.listen("set-repo-identity", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_abort_rebase();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.set_repo_identity(s);
+                        match result {
+                            Ok(()) => (),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
                 };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("continue-rebase", move |_event| {
+        main_window.listen("stage", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_continue_rebase();
-                match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
-                    Err(e) => handle_error(e, &main_window_c_c),
-                };
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_stage_from_json(s);
+                        match result {
+                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("discard-changes", move |event| {
+        main_window.listen("unstage", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_discard_changes(s);
+                        let result = git_manager.git_unstage(s);
                         match result {
                             Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
@@ -705,16 +776,16 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("delete-local-branch", move |event| {
+        main_window.listen("stage-lines", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
-                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_delete_local_branch(s);
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_stage_lines(s);
                         match result {
-                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
@@ -724,16 +795,16 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("delete-remote-branch", move |event| {
+        main_window.listen("unstage-lines", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
-                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_delete_remote_branch_from_json(s);
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_unstage_lines(s);
                         match result {
-                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
@@ -743,64 +814,766 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("delete-tag", move |event| {
+        main_window.listen("file-diff", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_file_diff(s);
+                        match result {
+                            Ok(file_lines) => main_window_c_c.emit_all("show-file-lines", file_lines).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-blame", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_blame(s);
+                        match result {
+                            Ok(blame) => main_window_c_c.emit_all("blame", blame).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-file-history", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_file_history(s);
+                        match result {
+                            Ok(file_history) => main_window_c_c.emit_all("file-history", file_history).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("stage-all", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_stage_all();
+                match result {
+                    Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("commit", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_commit_from_json(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-amend-info", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_amend_info();
+                match result {
+                    Ok(amend_info) => main_window_c_c.emit_all("amend-info", amend_info).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("amend", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_amend_from_json(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("commit-push", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_commit_from_json(s);
+                        match result {
+                            Ok(()) => {
+                                let result_2 = git_manager.git_push(None);
+                                match result_2 {
+                                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                                    Err(e) => handle_error(e, &main_window_c_c),
+                                };
+                            },
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("abort", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_abort();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("continue-cherrypick", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_continue_cherrypick();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("continue-revert", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_continue_revert();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("continue-merge", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_continue_merge();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("abort-rebase", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_abort_rebase();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("continue-rebase", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_continue_rebase();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-conflicts", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_conflicts();
+                match result {
+                    Ok(conflicts) => main_window_c_c.emit_all("conflicts", conflicts).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-conflict-versions", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_conflict_versions(s);
+                        match result {
+                            Ok(conflict_versions) => main_window_c_c.emit_all("conflict-versions", conflict_versions).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("resolve-conflict", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_resolve_conflict(s);
+                        match result {
+                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("discard-changes", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_discard_changes(s);
+                        match result {
+                            Ok(()) => emit_update_changes(&git_manager, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("delete-local-branch", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_delete_local_branch(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("delete-remote-branch", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_delete_remote_branch_from_json(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("delete-tag", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_delete_tag(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("fetch", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_fetch();
+                match result {
+                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-remotes", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_remotes();
+                match result {
+                    Ok(remotes) => main_window_c_c.emit_all("remotes", remotes).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("rename-remote", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_rename_remote(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("remove-remote", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_remove_remote(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("set-remote-url", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_set_remote_url(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("set-remote-fetch-refspecs", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_set_remote_fetch_refspecs(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("fetch-remote", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_fetch_remote(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("prune-remote", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_prune_remote(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("fast-forward-branch", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_fast_forward_branch(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("pull", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.git_pull();
+                // Even a failed pull can leave a merge or rebase in progress, so always refresh.
+                emit_update_all(&mut git_manager, false, &main_window_c_c);
+                if let Err(e) = result {
+                    handle_error(e, &main_window_c_c);
+                }
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("set-branch-pull-strategy", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.set_branch_pull_strategy(s);
+                        match result {
+                            Ok(()) => (),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("push", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_push(Some(s));
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("push-tag", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_delete_tag(s);
+                        let result = git_manager.git_push_tag(s);
                         match result {
                             Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
+                };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("fetch", move |_event| {
+        main_window.listen("get-submodules", move |_event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
-                let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_fetch();
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_submodules();
                 match result {
-                    Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                    Ok(submodules) => main_window_c_c.emit_all("submodules", submodules).unwrap(),
                     Err(e) => handle_error(e, &main_window_c_c),
                 };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("fast-forward-branch", move |event| {
+        main_window.listen("init-submodules", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_fast_forward_branch(s);
+                        let result = git_manager.git_init_submodules(s);
                         match result {
                             Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
                         };
                     },
                     None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
-                }
+                };
             });
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("pull", move |_event| {
+        main_window.listen("update-submodules", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_update_submodules(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("sync-submodules", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_sync_submodules(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("open-submodule", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.open_submodule(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, true, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-worktrees", move |_event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                let git_manager = git_manager_arc_c_c.lock().unwrap();
+                let result = git_manager.get_worktrees();
+                match result {
+                    Ok(worktrees) => main_window_c_c.emit_all("worktrees", worktrees).unwrap(),
+                    Err(e) => handle_error(e, &main_window_c_c),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("add-worktree", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_add_worktree(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("lock-worktree", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_lock_worktree(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("unlock-worktree", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_unlock_worktree(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("prune-worktrees", move |_event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                let result = git_manager.git_pull();
+                let result = git_manager.git_prune_worktrees();
                 match result {
                     Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
                     Err(e) => handle_error(e, &main_window_c_c),
@@ -809,14 +1582,14 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("push", move |event| {
+        main_window.listen("remove-worktree", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_push(Some(s));
+                        let result = git_manager.git_remove_worktree(s);
                         match result {
                             Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
@@ -828,14 +1601,33 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
-        main_window.listen("push-tag", move |event| {
+        main_window.listen("switch-worktree", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
             thread::spawn(move || {
                 match event.payload() {
                     Some(s) => {
                         let mut git_manager = git_manager_arc_c_c.lock().unwrap();
-                        let result = git_manager.git_push_tag(s);
+                        let result = git_manager.switch_worktree(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, true, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("push-tags", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_push_tags(s);
                         match result {
                             Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
                             Err(e) => handle_error(e, &main_window_c_c),
@@ -847,6 +1639,25 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-tag-info", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_tag_info(s);
+                        match result {
+                            Ok(tag_info) => main_window_c_c.emit_all("tag-info", tag_info).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
         main_window.listen("stash", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
@@ -885,6 +1696,44 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("get-stash-changes", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.get_stash_changes(s);
+                        match result {
+                            Ok(stash_changes) => main_window_c_c.emit_all("stash-changes", stash_changes).unwrap(),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("branch-from-stash", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_branch_from_stash(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
         main_window.listen("delete-stash", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
@@ -904,6 +1753,63 @@ fn main() {
         });
         let main_window_c = main_window.clone();
         let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("rename-branch", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_rename_branch(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("set-upstream", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_set_upstream(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
+        main_window.listen("unset-upstream", move |event| {
+            let main_window_c_c = main_window_c.clone();
+            let git_manager_arc_c_c = git_manager_arc_c.clone();
+            thread::spawn(move || {
+                match event.payload() {
+                    Some(s) => {
+                        let mut git_manager = git_manager_arc_c_c.lock().unwrap();
+                        let result = git_manager.git_unset_upstream(s);
+                        match result {
+                            Ok(()) => emit_update_all(&mut git_manager, false, &main_window_c_c),
+                            Err(e) => handle_error(e, &main_window_c_c),
+                        };
+                    },
+                    None => main_window_c_c.emit_all("error", "Failed to receive payload from front-end").unwrap(),
+                };
+            });
+        });
+        let main_window_c = main_window.clone();
+        let git_manager_arc_c = git_manager_arc.clone();
         main_window.listen("branch", move |event| {
             let main_window_c_c = main_window_c.clone();
             let git_manager_arc_c_c = git_manager_arc_c.clone();
@@ -5,7 +5,7 @@ use anyhow::{bail, Result};
 use git2::{BranchType, Diff, ErrorCode, Oid, RepositoryState};
 use serde::{Serialize, Deserialize, Serializer};
 use time::{format_description, OffsetDateTime};
-use crate::git_manager::GitManager;
+use crate::git_manager::{GitManager, GraphChange, OperationCancelled, CANCEL_CHECK_INTERVAL};
 use crate::svg_row::{RowProperty, SVGProperty, SVGRow};
 
 #[derive(Clone)]
@@ -31,13 +31,51 @@ impl Serialize for SVGCommitInfoValue {
 pub struct CommitsInfo {
     branch_draw_properties: Vec<(String, Vec<Vec<HashMap<String, SVGProperty>>>)>,
     svg_row_draw_properties: Vec<HashMap<String, RowProperty>>,
+    svg_row_delta: Option<SVGRowDelta>,
+    has_more_commits: bool,
 }
 
 impl CommitsInfo {
-    pub fn new(branch_draw_properties: Vec<(String, Vec<Vec<HashMap<String, SVGProperty>>>)>, svg_row_draw_properties: Vec<HashMap<String, RowProperty>>) -> Self {
+    pub fn new(branch_draw_properties: Vec<(String, Vec<Vec<HashMap<String, SVGProperty>>>)>, svg_row_draw_properties: Vec<HashMap<String, RowProperty>>, svg_row_delta: Option<SVGRowDelta>, has_more_commits: bool) -> Self {
         Self {
             branch_draw_properties,
             svg_row_draw_properties,
+            svg_row_delta,
+            has_more_commits,
+        }
+    }
+}
+
+// When only the top of the graph changed, the rows already drawn are kept. The removed rows are dropped from the top,
+// the remaining rows move down by the added row count, and only rows whose layout changed are sent again.
+#[derive(Clone, Serialize)]
+pub struct SVGRowDelta {
+    removed_row_count: usize,
+    added_row_count: usize,
+    changed_svg_row_draw_properties: Vec<HashMap<String, RowProperty>>,
+}
+
+impl SVGRowDelta {
+    pub fn new(removed_row_count: usize, added_row_count: usize, changed_svg_row_draw_properties: Vec<HashMap<String, RowProperty>>) -> Self {
+        Self {
+            removed_row_count,
+            added_row_count,
+            changed_svg_row_draw_properties,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct CommitGraphPage {
+    svg_row_draw_properties: Vec<HashMap<String, RowProperty>>,
+    has_more_commits: bool,
+}
+
+impl CommitGraphPage {
+    pub fn new(svg_row_draw_properties: Vec<HashMap<String, RowProperty>>, has_more_commits: bool) -> Self {
+        Self {
+            svg_row_draw_properties,
+            has_more_commits,
         }
     }
 }
@@ -99,6 +137,14 @@ impl ParseableCommitInfo {
     pub fn borrow_child_shas(&self) -> &Vec<String> {
         &self.child_shas
     }
+
+    pub fn borrow_child_shas_mut(&mut self) -> &mut Vec<String> {
+        &mut self.child_shas
+    }
+
+    pub fn set_y(&mut self, y: isize) {
+        self.y = y;
+    }
 }
 
 #[derive(Clone)]
@@ -172,10 +218,12 @@ pub struct BranchInfo {
     ahead: usize,
     behind: usize,
     has_upstream: bool,
+    // The path of another worktree that has this branch checked out.
+    checked_out_worktree_path: Option<String>,
 }
 
 impl BranchInfo {
-    pub fn new(target_sha: String, branch_shorthand: String, full_branch_name: String, is_head: bool, branch_type: String, ahead: usize, behind: usize, has_upstream: bool) -> Self {
+    pub fn new(target_sha: String, branch_shorthand: String, full_branch_name: String, is_head: bool, branch_type: String, ahead: usize, behind: usize, has_upstream: bool, checked_out_worktree_path: Option<String>) -> Self {
         Self {
             target_sha,
             branch_shorthand,
@@ -185,6 +233,7 @@ impl BranchInfo {
             ahead,
             behind,
             has_upstream,
+            checked_out_worktree_path,
         }
     }
 }
@@ -193,13 +242,15 @@ impl BranchInfo {
 pub struct StashInfo {
     index: usize,
     message: String,
+    sha: String,
 }
 
 impl StashInfo {
-    pub fn new(index: usize, message: String) -> Self {
+    pub fn new(index: usize, message: String, sha: String) -> Self {
         Self {
             index,
             message,
+            sha,
         }
     }
 }
@@ -327,13 +378,18 @@ fn get_oid_refs(git_manager: &GitManager) -> Result<HashMap<String, Vec<(String,
         if reference.is_tag() {
             let ref_name = GitManager::get_utf8_string(reference.shorthand(), "Tag Name")?;
 
+            // Annotated tags point at a tag object rather than straight at the commit.
+            let tag_type = match reference.target() {
+                Some(oid) if repo.find_tag(oid).is_ok() => "annotated_tag",
+                _ => "tag",
+            };
             let oid = reference.peel_to_commit()?.id();
             match oid_refs.get_mut(&*oid.to_string()) {
                 Some(oid_ref_vec) => {
-                    oid_ref_vec.push((ref_name.to_string(), "tag".to_string()));
+                    oid_ref_vec.push((ref_name.to_string(), tag_type.to_string()));
                 }
                 None => {
-                    oid_refs.insert(oid.to_string(), vec![(ref_name.to_string(), "tag".to_string())]);
+                    oid_refs.insert(oid.to_string(), vec![(ref_name.to_string(), tag_type.to_string())]);
                 },
             };
         }
@@ -402,17 +458,21 @@ fn get_general_info(git_manager: &GitManager) -> Result<HashMap<String, String>>
     general_info.insert(String::from("is_cherrypicking"), (repo_state == RepositoryState::CherryPick).to_string());
     general_info.insert(String::from("is_reverting"), (repo_state == RepositoryState::Revert).to_string());
     general_info.insert(String::from("is_merging"), (repo_state == RepositoryState::Merge).to_string());
-    general_info.insert(String::from("is_rebasing"), (repo_state == RepositoryState::Rebase || repo_state == RepositoryState::RebaseMerge || repo_state == RepositoryState::RebaseInteractive).to_string());
+    let is_rebasing = repo_state == RepositoryState::Rebase || repo_state == RepositoryState::RebaseMerge || repo_state == RepositoryState::RebaseInteractive || git_manager.is_interactive_rebasing()?;
+    general_info.insert(String::from("is_rebasing"), is_rebasing.to_string());
+    general_info.insert(String::from("squash_message"), git_manager.get_squash_message()?.unwrap_or_default());
 
     Ok(general_info)
 }
 
-fn get_commit_info_list(git_manager: &GitManager, oid_list: Vec<Oid>) -> Result<Vec<ParseableCommitInfo>> {
+fn get_commit_info_list(git_manager: &GitManager, oid_list: Vec<Oid>, starting_y: usize) -> Result<Vec<ParseableCommitInfo>> {
     let mut commit_list: Vec<ParseableCommitInfo> = vec![];
 
     let repo = git_manager.borrow_repo()?;
-    let mut children_oids_hm: HashMap<String, Vec<String>> = HashMap::new();
     for (i, oid) in oid_list.iter().enumerate() {
+        if i % CANCEL_CHECK_INTERVAL == 0 {
+            git_manager.borrow_cancel_handle().check()?;
+        }
         let commit = repo.find_commit(*oid)?;
 
         // Get commit summary
@@ -422,12 +482,6 @@ fn get_commit_info_list(git_manager: &GitManager, oid_list: Vec<Oid>) -> Result<
         let mut parent_shas: Vec<String> = vec![];
         for parent in commit.parents() {
             parent_shas.push(parent.id().to_string());
-            match children_oids_hm.get_mut(&*parent.id().to_string()) {
-                Some(children_oid_vec) => children_oid_vec.push(oid.to_string()),
-                None => {
-                    children_oids_hm.insert(parent.id().to_string(), vec![oid.to_string()]);
-                },
-            };
         }
 
         let author_signature = commit.author();
@@ -457,53 +511,113 @@ fn get_commit_info_list(git_manager: &GitManager, oid_list: Vec<Oid>) -> Result<
             author_name,
             formatted_datetime,
             0,
-            i as isize,
+            (starting_y + i) as isize,
             String::from(commit_summary),
             parent_shas,
             vec![])
         );
     }
 
+    set_child_shas(git_manager, &mut commit_list);
+
+    Ok(commit_list)
+}
+
+fn set_child_shas(git_manager: &GitManager, commit_list: &mut Vec<ParseableCommitInfo>) {
+    let mut children_oids_hm: HashMap<String, Vec<String>> = HashMap::new();
+    for commit_info in commit_list.iter() {
+        for parent_sha in &commit_info.parent_shas {
+            match children_oids_hm.get_mut(&*parent_sha) {
+                Some(children_oid_vec) => children_oid_vec.push(commit_info.sha.clone()),
+                None => {
+                    children_oids_hm.insert(parent_sha.clone(), vec![commit_info.sha.clone()]);
+                },
+            };
+        }
+    }
+
     // Gather the child commits after running through the commit graph once in order
     // to actually have populated entries.
     for commit_info in commit_list.iter_mut() {
+        // Children from earlier pages of the graph come first, just like they would in the revwalk.
+        let mut child_shas = git_manager.borrow_graph_lane_state().get_pending_child_shas(&*commit_info.sha);
         match children_oids_hm.get(&*commit_info.sha) {
-            Some(v) => {
-                commit_info.child_shas = v.clone();
-            },
+            Some(v) => child_shas.extend(v.iter().cloned()),
             None => (),
         };
+        commit_info.child_shas = child_shas;
     }
-
-    Ok(commit_list)
 }
 
-fn get_commit_svg_draw_properties_list(git_manager: &mut GitManager, force_refresh: bool) -> Result<CommitsInfo> {
-    let mut commit_info_list = vec![];
-    if let Some(oid_vec) = git_manager.git_revwalk(force_refresh)? {
-        commit_info_list = get_commit_info_list(git_manager, oid_vec)?;
+fn get_laid_out_svg_rows(git_manager: &mut GitManager, commit_info_list: Vec<ParseableCommitInfo>) -> Result<Vec<Rc<RefCell<SVGRow>>>> {
+    let mut svg_rows: Vec<Rc<RefCell<SVGRow>>> = vec![];
+    let mut svg_row_hm: HashMap<String, Rc<RefCell<SVGRow>>> = git_manager.borrow_graph_lane_state().get_pending_child_svg_rows();
+    for commit_info in &commit_info_list {
+        let svg_row_rc: Rc<RefCell<SVGRow>> = Rc::new(RefCell::new(SVGRow::from_commit_info(commit_info)));
+        svg_row_hm.insert(commit_info.sha.clone(), svg_row_rc.clone());
+        svg_rows.push(svg_row_rc);
     }
 
+    for svg_row_rc in &svg_rows {
+        svg_row_rc.borrow_mut().set_parent_and_child_svg_row_values(&svg_row_hm);
+    }
+
+    // This is the last chance to cancel before the lane state starts changing.
+    git_manager.borrow_cancel_handle().check()?;
+    let is_last_page = !git_manager.has_more_graph_commits();
+    let lane_state = git_manager.borrow_graph_lane_state_mut();
+    let page_row_cells = SVGRow::get_occupied_table(&svg_rows, lane_state, is_last_page)?;
+    for ((commit_info, svg_row_rc), row_cells) in commit_info_list.into_iter().zip(svg_rows.iter()).zip(page_row_cells) {
+        let row_layout = svg_row_rc.borrow().get_layout(lane_state.borrow_main_table());
+        lane_state.push_loaded_row(commit_info, row_layout, row_cells);
+    }
+
+    Ok(svg_rows)
+}
+
+fn get_svg_row_draw_properties(git_manager: &mut GitManager, oid_list: Vec<Oid>) -> Result<Vec<HashMap<String, RowProperty>>> {
+    let starting_y = git_manager.get_graph_loaded_count() - oid_list.len();
+    let commit_info_list = get_commit_info_list(git_manager, oid_list, starting_y)?;
+
     let mut svg_row_draw_properties: Vec<HashMap<String, RowProperty>> = vec![];
     if commit_info_list.len() > 0 {
-        let mut svg_rows: Vec<Rc<RefCell<SVGRow>>> = vec![];
-        let mut svg_row_hm: HashMap<String, Rc<RefCell<SVGRow>>> = HashMap::new();
-        for commit_info in commit_info_list {
-            let svg_row_rc: Rc<RefCell<SVGRow>> = Rc::new(RefCell::new(SVGRow::from_commit_info(&commit_info)));
-            svg_row_hm.insert(commit_info.sha.clone(), svg_row_rc.clone());
-            svg_rows.push(svg_row_rc);
+        let svg_rows = get_laid_out_svg_rows(git_manager, commit_info_list)?;
+        let main_table = git_manager.borrow_graph_lane_state().borrow_main_table();
+        for svg_row_rc in svg_rows {
+            svg_row_draw_properties.push(svg_row_rc.borrow_mut().get_draw_properties(main_table));
         }
+    }
 
-        for svg_row_rc in &svg_rows {
-            svg_row_rc.borrow_mut().set_parent_and_child_svg_row_values(&svg_row_hm);
-        }
+    Ok(svg_row_draw_properties)
+}
 
-        let main_table = SVGRow::get_occupied_table(&svg_rows)?;
-        for svg_row_rc in svg_rows {
-            svg_row_draw_properties.push(svg_row_rc.borrow_mut().get_draw_properties(
-                &main_table,
-            ));
+fn get_svg_row_delta(git_manager: &mut GitManager, removed_row_count: usize, added_oids: Vec<Oid>) -> Result<SVGRowDelta> {
+    // Only the added commits need to be looked up and laid out, the rest of the loaded rows keep their positions.
+    let added_row_count = added_oids.len();
+    let added_commit_info_list = get_commit_info_list(git_manager, added_oids, 0)?;
+
+    // This is the last chance to cancel before the lane state starts changing.
+    git_manager.borrow_cancel_handle().check()?;
+    let is_last_page = !git_manager.has_more_graph_commits();
+    let changed_svg_row_draw_properties = git_manager.borrow_graph_lane_state_mut().replace_top_rows(removed_row_count, added_commit_info_list, is_last_page)?;
+
+    Ok(SVGRowDelta::new(removed_row_count, added_row_count, changed_svg_row_draw_properties))
+}
+
+fn get_commit_svg_draw_properties_list(git_manager: &mut GitManager, force_refresh: bool) -> Result<CommitsInfo> {
+    let mut svg_row_draw_properties: Vec<HashMap<String, RowProperty>> = vec![];
+    let mut svg_row_delta = None;
+    let graph_change_result = match git_manager.git_revwalk(force_refresh)? {
+        Some(GraphChange::Full(oid_vec)) => get_svg_row_draw_properties(git_manager, oid_vec).map(|p| svg_row_draw_properties = p),
+        Some(GraphChange::TopOnly(removed_count, added_oids)) => get_svg_row_delta(git_manager, removed_count, added_oids).map(|d| svg_row_delta = Some(d)),
+        None => Ok(()),
+    };
+    if let Err(e) = graph_change_result {
+        // The new commits were only partly laid out, so start over on the next refresh.
+        if e.is::<OperationCancelled>() {
+            git_manager.reset_graph_state();
         }
+        return Err(e);
     }
 
     let oid_refs_hm = get_oid_refs(git_manager)?;
@@ -512,10 +626,32 @@ fn get_commit_svg_draw_properties_list(git_manager: &mut GitManager, force_refre
         branch_draw_properties.push((k, SVGRow::get_branch_draw_properties(v)));
     }
 
-    Ok(CommitsInfo::new(branch_draw_properties, svg_row_draw_properties))
+    Ok(CommitsInfo::new(branch_draw_properties, svg_row_draw_properties, svg_row_delta, git_manager.has_more_graph_commits()))
+}
+
+pub fn get_next_commit_graph_page(git_manager: &mut GitManager) -> Result<CommitGraphPage> {
+    if !git_manager.has_more_graph_commits() {
+        return Ok(CommitGraphPage::new(vec![], false));
+    }
+    git_manager.borrow_cancel_handle().start_step();
+    let oid_vec = git_manager.get_next_graph_page()?;
+    let page_len = oid_vec.len();
+    let svg_row_draw_properties = match get_svg_row_draw_properties(git_manager, oid_vec) {
+        Ok(p) => p,
+        Err(e) => {
+            // The lane state isn't touched until the page can no longer be cancelled, so only the page needs undoing.
+            if e.is::<OperationCancelled>() {
+                git_manager.unload_graph_page(page_len);
+            }
+            return Err(e);
+        },
+    };
+    Ok(CommitGraphPage::new(svg_row_draw_properties, git_manager.has_more_graph_commits()))
 }
 
 fn get_branch_info_list(git_manager: &mut GitManager) -> Result<BranchesInfo> {
+    // The worktree paths only label branches, so they shouldn't stop the rest of the repo from refreshing.
+    let branch_worktree_paths = git_manager.get_branch_worktree_paths().unwrap_or_default();
     let repo = git_manager.borrow_repo_mut()?;
 
     // Get all remote heads to be excluded from branches info
@@ -602,7 +738,8 @@ fn get_branch_info_list(git_manager: &mut GitManager) -> Result<BranchesInfo> {
         for s in branch_shorthand.split("/") {
             split_shorthand.push_back(String::from(s));
         }
-        let branch_info = BranchInfo::new(target_sha, branch_shorthand, full_branch_name, is_head, branch_type.clone(), ahead, behind, has_upstream);
+        let checked_out_worktree_path = branch_worktree_paths.get(&full_branch_name).cloned();
+        let branch_info = BranchInfo::new(target_sha, branch_shorthand, full_branch_name, is_head, branch_type.clone(), ahead, behind, has_upstream, checked_out_worktree_path);
         if branch_type == String::from("local") {
             local_branch_info_tree.insert_split_shorthand(split_shorthand, Some(branch_info));
         } else if branch_type == String::from("remote") {
@@ -621,8 +758,8 @@ fn get_branch_info_list(git_manager: &mut GitManager) -> Result<BranchesInfo> {
     }
 
     let mut stash_info_list = vec![];
-    repo.stash_foreach(|stash_index, stash_message, _stash_oid| {
-        let stash_info = StashInfo::new(stash_index, format!("{}: {}", stash_index, stash_message));
+    repo.stash_foreach(|stash_index, stash_message, stash_oid| {
+        let stash_info = StashInfo::new(stash_index, format!("{}: {}", stash_index, stash_message), stash_oid.to_string());
         stash_info_list.push(stash_info);
         true
     })?;
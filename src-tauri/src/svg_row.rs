@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 use anyhow::{bail, Result};
 use serde::{Serialize, Serializer};
@@ -81,6 +81,283 @@ const LINE_STROKE_WIDTH: isize = 2;
 const RECT_HEIGHT: isize = 18;
 const RECT_Y_OFFSET: isize = -(RECT_HEIGHT / 2);
 
+#[derive(Clone)]
+struct PendingParentLine {
+    parent_sha: String,
+    child_sha: String,
+    child_x: isize,
+    child_y: isize,
+    moved_x_val: isize,
+}
+
+// Everything a row's draw properties depend on, with child positions relative to the row so that rows which only
+// moved up or down still compare as equal.
+#[derive(Clone, PartialEq)]
+pub struct SVGRowLayout {
+    sha: String,
+    x: isize,
+    child_positions: Vec<(isize, isize)>,
+    largest_occupied_x: isize,
+}
+
+// The occupied table and the lines still waiting on parents that haven't been loaded yet, kept between pages of the graph.
+// The loaded rows are kept too, along with the cells each row's node and lines to its parents occupy (relative to the
+// row), so rows can be added or removed at the top without laying out the rest of the graph again.
+#[derive(Clone)]
+pub struct GraphLaneState {
+    main_table: Vec<Vec<isize>>,
+    pending_parent_lines: Vec<PendingParentLine>,
+    loaded_commit_infos: Vec<ParseableCommitInfo>,
+    loaded_row_layouts: Vec<SVGRowLayout>,
+    loaded_row_cells: Vec<Vec<(isize, isize)>>,
+}
+
+impl GraphLaneState {
+    pub fn new() -> Self {
+        Self {
+            main_table: vec![],
+            pending_parent_lines: vec![],
+            loaded_commit_infos: vec![],
+            loaded_row_layouts: vec![],
+            loaded_row_cells: vec![],
+        }
+    }
+
+    pub fn borrow_main_table(&self) -> &Vec<Vec<isize>> {
+        &self.main_table
+    }
+
+    pub fn push_loaded_row(&mut self, commit_info: ParseableCommitInfo, row_layout: SVGRowLayout, occupied_cells: Vec<(isize, isize)>) {
+        let row_y = *commit_info.borrow_y();
+        self.loaded_commit_infos.push(commit_info);
+        self.loaded_row_layouts.push(row_layout);
+        self.loaded_row_cells.push(occupied_cells.into_iter().map(|(y, x)| (y - row_y, x)).collect());
+    }
+
+    fn add_loaded_row_cells(&mut self, row_y: isize, occupied_cells: Vec<(isize, isize)>) {
+        if let Some(row_cells) = self.loaded_row_cells.get_mut(row_y as usize) {
+            row_cells.extend(occupied_cells.into_iter().map(|(y, x)| (y - row_y, x)));
+        }
+    }
+
+    // Children always come before their parents, so this only has to look above the given row.
+    fn find_loaded_row(&self, sha: &str, before_y: usize) -> Option<usize> {
+        self.loaded_commit_infos[..before_y.min(self.loaded_commit_infos.len())].iter().position(|commit_info| commit_info.borrow_sha() == sha)
+    }
+
+    // Rebuilds a loaded row with its children where they are now, so its layout can be checked again.
+    fn get_loaded_svg_row(&self, y: usize) -> SVGRow {
+        let commit_info = &self.loaded_commit_infos[y];
+        let mut svg_row = SVGRow::from_commit_info(commit_info);
+        svg_row.x = self.loaded_row_layouts[y].x;
+        for child_sha in commit_info.borrow_child_shas() {
+            if let Some(child_y) = self.find_loaded_row(child_sha, y) {
+                svg_row.child_svg_rows.push(Rc::new(RefCell::new(SVGRow::from_position(child_sha.clone(), self.loaded_row_layouts[child_y].x, child_y as isize))));
+            }
+        }
+        svg_row.has_parent_child_svg_rows_set = true;
+        svg_row
+    }
+
+    // Drops rows from the top of the graph and lays out the new rows above the ones that stay, without moving any of them.
+    // A line from a new row down to a loaded parent gets a lane that's free the whole way, so nothing below has to be
+    // laid out again. Returns the draw properties of the new rows and of each remaining row whose layout changed, top to bottom.
+    pub fn replace_top_rows(&mut self, removed_row_count: usize, added_commit_infos: Vec<ParseableCommitInfo>, is_last_page: bool) -> Result<Vec<HashMap<String, RowProperty>>> {
+        let removed_row_count = removed_row_count.min(self.loaded_commit_infos.len());
+        let added_row_count = added_commit_infos.len();
+        let mut changed_ys: BTreeSet<usize> = BTreeSet::new();
+
+        // Free up everything the removed rows occupied in the rows that stay.
+        for (i, row_cells) in self.loaded_row_cells[..removed_row_count].iter().enumerate() {
+            for (relative_y, x) in row_cells {
+                let y = (i as isize + relative_y) as usize;
+                if y < removed_row_count {
+                    continue;
+                }
+                if let Some(table_row) = self.main_table.get_mut(y) {
+                    if let Some(position) = table_row.iter().position(|x_val| x_val == x) {
+                        table_row.remove(position);
+                    }
+                    changed_ys.insert(y - removed_row_count + added_row_count);
+                }
+            }
+        }
+        let removed_shas: Vec<String> = self.loaded_commit_infos[..removed_row_count].iter().map(|commit_info| commit_info.borrow_sha().clone()).collect();
+        let removed_parent_shas: Vec<String> = self.loaded_commit_infos[..removed_row_count].iter().flat_map(|commit_info| commit_info.borrow_parent_shas().clone()).collect();
+
+        self.main_table.splice(..removed_row_count, vec![vec![]; added_row_count]);
+        self.loaded_commit_infos.drain(..removed_row_count);
+        self.loaded_row_layouts.drain(..removed_row_count);
+        self.loaded_row_cells.drain(..removed_row_count);
+        self.pending_parent_lines.retain(|pending_parent_line| pending_parent_line.child_y >= removed_row_count as isize);
+        for pending_parent_line in &mut self.pending_parent_lines {
+            pending_parent_line.child_y += added_row_count as isize - removed_row_count as isize;
+        }
+        for parent_sha in &removed_parent_shas {
+            if let Some(i) = self.find_loaded_row(parent_sha, self.loaded_commit_infos.len()) {
+                self.loaded_commit_infos[i].borrow_child_shas_mut().retain(|child_sha| !removed_shas.contains(child_sha));
+                changed_ys.insert(i + added_row_count);
+            }
+        }
+
+        // Loaded parents keep their positions, which are now below the new rows.
+        let mut svg_rows: Vec<Rc<RefCell<SVGRow>>> = vec![];
+        let mut svg_row_hm: HashMap<String, Rc<RefCell<SVGRow>>> = HashMap::new();
+        for commit_info in &added_commit_infos {
+            let svg_row_rc = Rc::new(RefCell::new(SVGRow::from_commit_info(commit_info)));
+            svg_row_hm.insert(commit_info.borrow_sha().clone(), svg_row_rc.clone());
+            svg_rows.push(svg_row_rc);
+        }
+        let mut loaded_parent_children: HashMap<usize, Vec<String>> = HashMap::new();
+        for commit_info in &added_commit_infos {
+            for parent_sha in commit_info.borrow_parent_shas() {
+                let loaded_parent_i = match svg_row_hm.get(parent_sha) {
+                    Some(svg_row_rc) if svg_row_rc.borrow().y < added_row_count as isize => continue,
+                    Some(svg_row_rc) => Some(svg_row_rc.borrow().y as usize - added_row_count),
+                    None => self.find_loaded_row(parent_sha, self.loaded_commit_infos.len()),
+                };
+                if let Some(i) = loaded_parent_i {
+                    svg_row_hm.entry(parent_sha.clone()).or_insert_with(|| {
+                        Rc::new(RefCell::new(SVGRow::from_position(parent_sha.clone(), self.loaded_row_layouts[i].x, (i + added_row_count) as isize)))
+                    });
+                    loaded_parent_children.entry(i).or_insert_with(Vec::new).push(commit_info.borrow_sha().clone());
+                }
+            }
+        }
+        for svg_row_rc in &svg_rows {
+            svg_row_rc.borrow_mut().set_parent_and_child_svg_row_values(&svg_row_hm);
+        }
+
+        let table_len = self.main_table.len() as isize;
+        let mut new_row_cells: Vec<Vec<(isize, isize)>> = vec![vec![]; added_row_count];
+        for (i, svg_row_rc) in svg_rows.iter().enumerate() {
+            let mut svg_row = svg_row_rc.borrow_mut();
+            let row_cells = &mut new_row_cells[i];
+            let y = svg_row.y;
+
+            // The straight part of a line to a loaded parent is drawn in the child's lane as long as the child isn't left
+            // of the parent, so keep the node in a lane that's free down to its lowest loaded parent.
+            let mut x = svg_row.x;
+            let mut lane_end_y = y + 1;
+            for parent_svg_row_rc in &svg_row.parent_svg_rows {
+                let parent_svg_row = parent_svg_row_rc.borrow();
+                if parent_svg_row.y >= added_row_count as isize {
+                    x = x.max(parent_svg_row.x);
+                    lane_end_y = lane_end_y.max(parent_svg_row.y);
+                }
+            }
+            if !is_last_page && !svg_row.unloaded_parent_oids.is_empty() {
+                lane_end_y = table_len;
+            }
+            while (y..lane_end_y).any(|row_y| self.main_table[row_y as usize].contains(&x)) {
+                x += 1;
+            }
+            svg_row.x = x;
+            self.main_table[y as usize].push(x);
+            row_cells.push((y, x));
+
+            for parent_sha in &svg_row.parent_oids {
+                let parent_svg_row_rc_opt = svg_row.parent_svg_rows.iter().find(|r| r.borrow().sha == *parent_sha);
+                match parent_svg_row_rc_opt {
+                    Some(parent_svg_row_rc) => {
+                        let mut parent_svg_row = parent_svg_row_rc.borrow_mut();
+                        if parent_svg_row.y < added_row_count as isize {
+                            let mut moved_x_val = 0;
+                            SVGRow::occupy_line_rows(&mut self.main_table, x, y + 1, parent_svg_row.y, &mut moved_x_val, row_cells);
+                            // This is used particularly for merging lines
+                            parent_svg_row.x = moved_x_val;
+                        } else {
+                            for row_y in y + 1..parent_svg_row.y {
+                                self.main_table[row_y as usize].push(x);
+                                row_cells.push((row_y, x));
+                            }
+                        }
+                    },
+                    None => {
+                        if !is_last_page && svg_row.unloaded_parent_oids.contains(parent_sha) {
+                            for row_y in y + 1..table_len {
+                                self.main_table[row_y as usize].push(x);
+                                row_cells.push((row_y, x));
+                            }
+                            self.pending_parent_lines.push(PendingParentLine {
+                                parent_sha: parent_sha.clone(),
+                                child_sha: svg_row.sha.clone(),
+                                child_x: x,
+                                child_y: y,
+                                moved_x_val: 0,
+                            });
+                        }
+                    },
+                };
+            }
+        }
+        for (i, svg_row_rc) in svg_rows.iter().enumerate() {
+            SVGRow::occupy_curved_line_rows(&mut self.main_table, &svg_row_rc.borrow(), &mut new_row_cells[i]);
+        }
+
+        let mut changed_svg_row_draw_properties: Vec<HashMap<String, RowProperty>> = vec![];
+        let mut new_row_layouts: Vec<SVGRowLayout> = vec![];
+        for svg_row_rc in &svg_rows {
+            new_row_layouts.push(svg_row_rc.borrow().get_layout(&self.main_table));
+            changed_svg_row_draw_properties.push(svg_row_rc.borrow_mut().get_draw_properties(&self.main_table));
+        }
+
+        // The new rows go above the loaded parents' earlier children, just like they would in the revwalk.
+        for (i, mut child_shas) in loaded_parent_children {
+            child_shas.extend(self.loaded_commit_infos[i].borrow_child_shas().iter().cloned());
+            *self.loaded_commit_infos[i].borrow_child_shas_mut() = child_shas;
+            changed_ys.insert(i + added_row_count);
+        }
+        for row_cells in &new_row_cells {
+            for (y, _) in row_cells {
+                if *y >= added_row_count as isize {
+                    changed_ys.insert(*y as usize);
+                }
+            }
+        }
+        let new_row_cells: Vec<Vec<(isize, isize)>> = new_row_cells.into_iter().enumerate().map(|(i, row_cells)| {
+            row_cells.into_iter().map(|(y, x)| (y - i as isize, x)).collect()
+        }).collect();
+        self.loaded_commit_infos.splice(..0, added_commit_infos);
+        self.loaded_row_layouts.splice(..0, new_row_layouts);
+        self.loaded_row_cells.splice(..0, new_row_cells);
+        for (i, commit_info) in self.loaded_commit_infos.iter_mut().enumerate().skip(added_row_count) {
+            commit_info.set_y(i as isize);
+        }
+
+        for y in changed_ys {
+            if y >= self.loaded_commit_infos.len() {
+                continue;
+            }
+            let mut svg_row = self.get_loaded_svg_row(y);
+            let row_layout = svg_row.get_layout(&self.main_table);
+            if row_layout != self.loaded_row_layouts[y] {
+                changed_svg_row_draw_properties.push(svg_row.get_draw_properties(&self.main_table));
+                self.loaded_row_layouts[y] = row_layout;
+            }
+        }
+
+        Ok(changed_svg_row_draw_properties)
+    }
+
+    pub fn get_pending_child_shas(&self, parent_sha: &str) -> Vec<String> {
+        self.pending_parent_lines.iter().filter(|l| l.parent_sha == parent_sha).map(|l| l.child_sha.clone()).collect()
+    }
+
+    // Children from earlier pages are only needed for their positions, so they're added as rows that won't be laid out again.
+    pub fn get_pending_child_svg_rows(&self) -> HashMap<String, Rc<RefCell<SVGRow>>> {
+        let mut pending_child_svg_rows = HashMap::new();
+        for pending_parent_line in &self.pending_parent_lines {
+            pending_child_svg_rows.insert(pending_parent_line.child_sha.clone(), Rc::new(RefCell::new(SVGRow::from_position(
+                pending_parent_line.child_sha.clone(),
+                pending_parent_line.child_x,
+                pending_parent_line.child_y,
+            ))));
+        }
+        pending_child_svg_rows
+    }
+}
+
 #[derive(Clone)]
 pub struct SVGRow {
     sha: String,
@@ -90,6 +367,7 @@ pub struct SVGRow {
     parent_oids: Vec<String>,
     child_oids: Vec<String>,
     has_parent_child_svg_rows_set: bool,
+    unloaded_parent_oids: Vec<String>,
     parent_svg_rows: Vec<Rc<RefCell<SVGRow>>>,
     child_svg_rows: Vec<Rc<RefCell<SVGRow>>>,
     x: isize,
@@ -106,6 +384,7 @@ impl SVGRow {
             parent_oids: commit_info.borrow_parent_shas().clone(),
             child_oids: commit_info.borrow_child_shas().clone(),
             has_parent_child_svg_rows_set: false,
+            unloaded_parent_oids: vec![],
             parent_svg_rows: vec![],
             child_svg_rows: vec![],
             x: commit_info.borrow_x().clone(),
@@ -113,14 +392,31 @@ impl SVGRow {
         }
     }
 
+    fn from_position(sha: String, x: isize, y: isize) -> Self {
+        Self {
+            sha,
+            author_name: String::new(),
+            author_time: String::new(),
+            summary: String::new(),
+            parent_oids: vec![],
+            child_oids: vec![],
+            has_parent_child_svg_rows_set: true,
+            unloaded_parent_oids: vec![],
+            parent_svg_rows: vec![],
+            child_svg_rows: vec![],
+            x,
+            y,
+        }
+    }
+
     pub fn set_parent_and_child_svg_row_values(&mut self, all_svg_rows: &HashMap<String, Rc<RefCell<SVGRow>>>) {
         for sha in &self.parent_oids {
             match all_svg_rows.get(&*sha) {
                 Some(svg_row_rc) => {
                     self.parent_svg_rows.push(svg_row_rc.clone());
                 },
-                // If a parent is not present, ignore it. It may be outside the revwalk range.
-                None => (),
+                // If a parent is not present, it may be in a page that hasn't been loaded yet or outside the revwalk range.
+                None => self.unloaded_parent_oids.push(sha.clone()),
             };
         }
 
@@ -150,11 +446,63 @@ impl SVGRow {
         }
     }
 
-    pub fn get_occupied_table(svg_rows: &Vec<Rc<RefCell<SVGRow>>>) -> Result<Vec<Vec<isize>>> {
-        let mut main_table: Vec<Vec<isize>> = vec![];
+    fn occupy_line_rows(main_table: &mut Vec<Vec<isize>>, x: isize, start_y: isize, end_y: isize, moved_x_val: &mut isize, occupied_cells: &mut Vec<(isize, isize)>) {
+        for i in start_y..end_y {
+            let mut x_val = x;
+            if i < main_table.len() as isize {
+                while main_table[i as usize].contains(&x_val) {
+                    x_val += 1;
+                    // Note: this has to stay in the loop so it's only set when x changes!
+                    // and not just to svg_row.x
+                    *moved_x_val = x_val;
+                }
+                main_table[i as usize].push(x_val);
+            } else {
+                main_table.push(vec![x_val]);
+            }
+            occupied_cells.push((i, x_val));
+        }
+    }
+
+    // Returns the cells occupied by each row's node and lines to its parents.
+    pub fn get_occupied_table(svg_rows: &Vec<Rc<RefCell<SVGRow>>>, lane_state: &mut GraphLaneState, is_last_page: bool) -> Result<Vec<Vec<(isize, isize)>>> {
+        let (page_start_y, page_end_y) = match (svg_rows.first(), svg_rows.last()) {
+            (Some(first), Some(last)) => (first.borrow().y, last.borrow().y + 1),
+            _ => return Ok(vec![]),
+        };
+        let main_table = &mut lane_state.main_table;
+
+        // Continue the lines from earlier pages first, since their children came before every row in this page.
+        let mut resolved_parent_lines = vec![];
+        let mut still_pending_parent_lines = vec![];
+        let mut earlier_row_cells = vec![];
+        for mut pending_parent_line in lane_state.pending_parent_lines.drain(..) {
+            let parent_svg_row_opt = svg_rows.iter().find(|r| r.borrow().sha == pending_parent_line.parent_sha);
+            let end_y = match parent_svg_row_opt {
+                Some(parent_svg_row_rc) => parent_svg_row_rc.borrow().y,
+                None => page_end_y,
+            };
+            let mut line_cells = vec![];
+            SVGRow::occupy_line_rows(main_table, pending_parent_line.child_x, page_start_y, end_y, &mut pending_parent_line.moved_x_val, &mut line_cells);
+            earlier_row_cells.push((pending_parent_line.child_y, line_cells));
+            match parent_svg_row_opt {
+                Some(parent_svg_row_rc) => {
+                    parent_svg_row_rc.borrow_mut().x = pending_parent_line.moved_x_val;
+                    resolved_parent_lines.push((pending_parent_line, parent_svg_row_rc.clone()));
+                },
+                // On the last page, a parent that still hasn't shown up is outside the revwalk range, so ignore it.
+                None => {
+                    if !is_last_page {
+                        still_pending_parent_lines.push(pending_parent_line);
+                    }
+                },
+            };
+        }
 
-        for svg_row_rc in svg_rows.iter() {
+        let mut page_row_cells: Vec<Vec<(isize, isize)>> = vec![vec![]; svg_rows.len()];
+        for (i, svg_row_rc) in svg_rows.iter().enumerate() {
             let mut svg_row = svg_row_rc.borrow_mut();
+            let row_cells = &mut page_row_cells[i];
 
             if !svg_row.has_parent_child_svg_rows_set {
                 bail!("SVGRow object didn't have parents or children set. Make sure 'set_parent_and_child_svg_row_values' is run before 'get_occupied_table'!");
@@ -169,47 +517,86 @@ impl SVGRow {
             } else {
                 main_table.push(vec![svg_row.x]);
             }
+            row_cells.push((svg_row.y, svg_row.x));
 
             // Set the space of the line from the current node to its parents as occupied.
-            for parent_svg_row_rc in &svg_row.parent_svg_rows {
-                let mut parent_svg_row = parent_svg_row_rc.borrow_mut();
+            for parent_sha in &svg_row.parent_oids {
+                let parent_svg_row_rc_opt = svg_row.parent_svg_rows.iter().find(|r| r.borrow().sha == *parent_sha);
                 let mut moved_x_val = 0;
-                for i in (svg_row.y + 1)..parent_svg_row.y {
-                    let mut x_val = svg_row.x;
-                    if i < main_table.len() as isize {
-                        while main_table[i as usize].contains(&x_val) {
-                            x_val += 1;
-                            // Note: this has to stay in the loop so it's only set when x changes!
-                            // and not just to svg_row.x
-                            moved_x_val = x_val;
+                match parent_svg_row_rc_opt {
+                    Some(parent_svg_row_rc) => {
+                        let mut parent_svg_row = parent_svg_row_rc.borrow_mut();
+                        SVGRow::occupy_line_rows(main_table, svg_row.x, svg_row.y + 1, parent_svg_row.y, &mut moved_x_val, row_cells);
+                        // This is used particularly for merging lines
+                        parent_svg_row.x = moved_x_val;
+                    },
+                    None => {
+                        if !is_last_page && svg_row.unloaded_parent_oids.contains(parent_sha) {
+                            SVGRow::occupy_line_rows(main_table, svg_row.x, svg_row.y + 1, page_end_y, &mut moved_x_val, row_cells);
+                            still_pending_parent_lines.push(PendingParentLine {
+                                parent_sha: parent_sha.clone(),
+                                child_sha: svg_row.sha.clone(),
+                                child_x: svg_row.x,
+                                child_y: svg_row.y,
+                                moved_x_val,
+                            });
                         }
-                        main_table[i as usize].push(x_val);
-                    } else {
-                        main_table.push(vec![x_val]);
-                    }
-                }
-                // This is used particularly for merging lines
-                parent_svg_row.x = moved_x_val;
+                    },
+                };
             }
         }
+        lane_state.pending_parent_lines = still_pending_parent_lines;
 
         // Loop through after everything's set in order to properly occupy spaces by curved lines just for summary text positions.
-        for svg_row_rc in svg_rows {
-            let svg_row = svg_row_rc.borrow();
+        for (i, svg_row_rc) in svg_rows.iter().enumerate() {
+            SVGRow::occupy_curved_line_rows(main_table, &svg_row_rc.borrow(), &mut page_row_cells[i]);
+        }
+        for (resolved_parent_line, parent_svg_row_rc) in resolved_parent_lines {
+            let parent_svg_row = parent_svg_row_rc.borrow();
+            let mut line_cells = vec![];
+            if resolved_parent_line.child_x < parent_svg_row.x {
+                // The child's row was already sent with an earlier page, so this only keeps the table consistent.
+                main_table[resolved_parent_line.child_y as usize].push(parent_svg_row.x);
+                line_cells.push((resolved_parent_line.child_y, parent_svg_row.x));
+            } else if resolved_parent_line.child_x > parent_svg_row.x {
+                main_table[parent_svg_row.y as usize].push(resolved_parent_line.child_x);
+                line_cells.push((parent_svg_row.y, resolved_parent_line.child_x));
+            }
+            earlier_row_cells.push((resolved_parent_line.child_y, line_cells));
+        }
 
-            for parent_svg_row_rc in &svg_row.parent_svg_rows {
-                let parent_svg_row = parent_svg_row_rc.borrow();
-                if svg_row.x < parent_svg_row.x {
-                    let x_val = parent_svg_row.x;
-                    main_table[svg_row.y as usize].push(x_val);
-                } else if svg_row.x > parent_svg_row.x {
-                    let x_val = svg_row.x;
-                    main_table[parent_svg_row.y as usize].push(x_val);
-                }
+        for (child_y, line_cells) in earlier_row_cells {
+            lane_state.add_loaded_row_cells(child_y, line_cells);
+        }
+
+        Ok(page_row_cells)
+    }
+
+    fn occupy_curved_line_rows(main_table: &mut Vec<Vec<isize>>, svg_row: &SVGRow, occupied_cells: &mut Vec<(isize, isize)>) {
+        for parent_svg_row_rc in &svg_row.parent_svg_rows {
+            let parent_svg_row = parent_svg_row_rc.borrow();
+            if svg_row.x < parent_svg_row.x {
+                let x_val = parent_svg_row.x;
+                main_table[svg_row.y as usize].push(x_val);
+                occupied_cells.push((svg_row.y, x_val));
+            } else if svg_row.x > parent_svg_row.x {
+                let x_val = svg_row.x;
+                main_table[parent_svg_row.y as usize].push(x_val);
+                occupied_cells.push((parent_svg_row.y, x_val));
             }
         }
+    }
 
-        Ok(main_table)
+    pub fn get_layout(&self, main_table: &Vec<Vec<isize>>) -> SVGRowLayout {
+        SVGRowLayout {
+            sha: self.sha.clone(),
+            x: self.x,
+            child_positions: self.child_svg_rows.iter().map(|child_svg_row_rc| {
+                let child_svg_row = child_svg_row_rc.borrow();
+                (child_svg_row.x, self.y - child_svg_row.y)
+            }).collect(),
+            largest_occupied_x: *main_table[self.y as usize].iter().max().unwrap_or(&0),
+        }
     }
 
     pub fn get_draw_properties(&mut self, main_table: &Vec<Vec<isize>>) -> HashMap<String, RowProperty> {
@@ -405,11 +792,17 @@ impl SVGRow {
                 branch_rect_color = "red";
             } else if branch_type == "remote" {
                 branch_rect_color = "green";
-            } else if branch_type == "tag" {
+            } else if branch_type == "tag" || branch_type == "annotated_tag" {
                 branch_rect_color = "grey";
             }
 
-            let style_str = String::from("fill:") + branch_rect_color + ";fill-opacity:0.5;";
+            let mut style_str = String::from("fill:") + branch_rect_color + ";fill-opacity:0.5;";
+            // Lightweight tags get a dashed outline so they stand apart from annotated ones.
+            if branch_type == "tag" {
+                style_str += "stroke:white;stroke-dasharray:4 2;";
+            } else if branch_type == "annotated_tag" {
+                style_str += "stroke:white;";
+            }
             let rect_attrs: HashMap<String, SVGPropertyAttrs> = HashMap::from([
                 (String::from("x"), SVGPropertyAttrs::SomeInt(0)),
                 (String::from("y"), SVGPropertyAttrs::SomeInt(RECT_Y_OFFSET)),
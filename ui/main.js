@@ -27,6 +27,8 @@ class Main {
         this.selectedFileChangedInfoFilePath = '';
         this.commitFileDiffTableScrollTop = 0;
         this.fileDiffTableScrollTop = 0;
+        this.unknownHostKey = null;
+        this.squashMessage = '';
     }
 
     run() {
@@ -132,6 +134,10 @@ class Main {
             self.removeProcessCount();
         }).then();
 
+        listen("commit-graph-page", ev => {
+            self.svgManager.addGraphPage(ev.payload);
+        }).then();
+
         listen("update_changes", ev => {
             self.showRepoView();
             self.updateFilesChangedInfo(ev.payload);
@@ -176,10 +182,24 @@ class Main {
         }).then();
 
         listen("error", ev => {
+            self.svgManager.isLoadingMoreCommits = false;
             self.removeProcessCount();
             self.showError(ev.payload);
         }).then();
 
+        listen("cancelled", ev => {
+            self.svgManager.isLoadingMoreCommits = false;
+            self.removeProcessCount();
+        }).then();
+
+        listen("unknown-host-key", ev => {
+            self.svgManager.isLoadingMoreCommits = false;
+            self.removeProcessCount();
+            self.unknownHostKey = ev.payload;
+            $('#unknownHostKeyMessage').text("The authenticity of host '" + ev.payload['hostname'] + "' can't be established. Its " + ev.payload['key_type_short_name'] + " key fingerprint is " + ev.payload['fingerprint'] + ".");
+            $('#unknownHostKeyModal').modal('show');
+        }).then();
+
         $('#updateBtn').click(async function() {
             const $updaterSpinner = $('#updaterSpinner');
             $updaterSpinner.show();
@@ -348,6 +368,19 @@ class Main {
             emit("continue-cherrypick").then();
         });
 
+        $('#acceptHostKeyBtn').click(() => {
+            if (self.unknownHostKey !== null) {
+                emit("accept-host-key", {
+                    hostname: self.unknownHostKey['hostname'],
+                    key_type: self.unknownHostKey['key_type'],
+                    key: self.unknownHostKey['key'],
+                    persist: $('#rememberHostKeyCheckBox').is(':checked').toString(),
+                }).then();
+                self.unknownHostKey = null;
+            }
+            $('#unknownHostKeyModal').modal('hide');
+        });
+
         $('#cherrypickBtn').click(() => {
             const $cherrypickSha = $('#cherrypickSha');
             emit("cherrypick", {sha: $cherrypickSha.text(), isCommitting: $('#commitCherrypickCheckBox').is(':checked').toString()}).then();
@@ -750,6 +783,20 @@ class Main {
         } else {
             self.showCommitControls();
         }
+
+        // Fill in the message prepared by a squash merge once, without replacing anything already typed.
+        const squashMessage = self.generalInfo['squash_message'];
+        if (squashMessage !== self.squashMessage) {
+            self.squashMessage = squashMessage;
+            const $summaryTxt = $('#summaryTxt'),
+                $messageTxt = $('#messageTxt');
+            if (squashMessage !== '' && $summaryTxt.val() === '' && $messageTxt.val() === '') {
+                const lines = squashMessage.split('\n');
+                $summaryTxt.val(lines[0]);
+                $messageTxt.val(lines.slice(1).join('\n').trim());
+                self.updateSummaryTxtCounter();
+            }
+        }
     }
 
     prependFileIcon($row, status) {
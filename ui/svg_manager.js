@@ -21,6 +21,9 @@ export class SVGManager {
         this.commitsTop = -99;
         this.commitsBottom = -99;
         this.selectedSHA = '';
+        this.branchDrawProperties = [];
+        this.hasMoreCommits = false;
+        this.isLoadingMoreCommits = false;
         this.mainJS = mainJS;
         this.setScrollEvent();
     }
@@ -49,51 +52,19 @@ export class SVGManager {
         }
 
         const graphWidth = Number(self.commitTableSVG.getAttribute('width'));
-        if (commitsInfo['svg_row_draw_properties'].length > 0) {
+        if (commitsInfo['svg_row_delta'] !== null) {
+            self.applyRowDelta(commitsInfo['svg_row_delta'], graphWidth, singleCharWidth);
+        } else if (commitsInfo['svg_row_draw_properties'].length > 0) {
             self.rows = [];
 
             for (let i = 0; i < commitsInfo['svg_row_draw_properties'].length; i++) {
-                const commit = commitsInfo['svg_row_draw_properties'][i];
-                const elements = commit['elements'];
-                let row = {'sha': commit['sha'], 'pixel_y': commit['pixel_y'], 'lines': [], 'branches': [], 'circle': null, 'summaryTxt': null, 'authorName': null, 'authorTime': null, 'backRect': null};
-                for (const childLine of elements['child_lines']) {
-                    const line = self.makeSVG(childLine['tag'], childLine['attrs']);
-                    if (childLine['row-y'] < i) {
-                        self.rows[childLine['row-y']]['lines'].push(line);
-                    } else if (childLine['row-y'] === i) {
-                        row['lines'].push(line);
-                    } else {
-                        console.error("ERROR: A child line is trying to be added after the current node!");
-                    }
-                }
-                row['circle'] = self.makeSVG(elements['circle']['tag'], elements['circle']['attrs']);
-
-                const summaryTxt = self.makeSVG(elements['summary_text']['tag'], elements['summary_text']['attrs']);
-                summaryTxt.textContent = elements['summary_text']['textContent'];
-                row['summaryTxt'] = summaryTxt;
-
-                const authorTimeX = graphWidth - (elements['author_time']['textContent'].length * singleCharWidth) - self.RIGHT_TEXT_SPACING;
-                elements['author_time']['attrs']['x'] = authorTimeX;
-                const authorTime = self.makeSVG(elements['author_time']['tag'], elements['author_time']['attrs']);
-                authorTime.textContent = elements['author_time']['textContent'];
-                row['authorTime'] = authorTime;
-
-                elements['author_name']['attrs']['x'] = authorTimeX - (elements['author_name']['textContent'].length * singleCharWidth) - self.RIGHT_TEXT_SPACING;
-                const authorName = self.makeSVG(elements['author_name']['tag'], elements['author_name']['attrs']);
-                authorName.textContent = elements['author_name']['textContent'];
-                row['authorName'] = authorName;
-
-                elements['back_rect']['attrs']['width'] = graphWidth - elements['circle']['attrs']['cx'];
-                const backRect = self.makeSVG(elements['back_rect']['tag'], elements['back_rect']['attrs']);
-                backRect.onclick = self.getClickFunction(commit['sha']);
-                backRect.ondblclick = self.getDblClickFunction(commit['sha']);
-                backRect.oncontextmenu = self.getContextFunction(commit['sha']);
-                row['backRect'] = backRect;
-
-                self.rows.push(row);
+                self.rows.push(self.makeRow(commitsInfo['svg_row_draw_properties'][i], i, [], graphWidth, singleCharWidth));
             }
         }
 
+        // Kept so rows from later pages can get their branch labels too.
+        self.branchDrawProperties = JSON.parse(JSON.stringify(commitsInfo['branch_draw_properties']));
+        self.hasMoreCommits = commitsInfo['has_more_commits'];
         self.addBranchLabels(commitsInfo['branch_draw_properties'], singleCharWidth);
         for (let i = 0; i < self.rows.length; i++) {
             self.truncateSummaryTxt(i, singleCharWidth);
@@ -104,6 +75,117 @@ export class SVGManager {
         self.selectRowOnRefresh(headSHA);
     }
 
+    /**
+     * Adds the next page of the graph below the rows already loaded.
+     */
+    addGraphPage(commitGraphPage) {
+        const self = this,
+            singleCharWidth = self.getSingleCharWidth(),
+            graphWidth = Number(self.commitTableSVG.getAttribute('width')),
+            startIndex = self.rows.length;
+
+        for (let i = 0; i < self.rows.length; i++) {
+            self.removeBranchLabels(self.rows[i]);
+        }
+        for (let i = 0; i < commitGraphPage['svg_row_draw_properties'].length; i++) {
+            self.rows.push(self.makeRow(commitGraphPage['svg_row_draw_properties'][i], startIndex + i, [], graphWidth, singleCharWidth));
+        }
+        self.addBranchLabels(JSON.parse(JSON.stringify(self.branchDrawProperties)), singleCharWidth);
+        for (let i = 0; i < self.rows.length; i++) {
+            self.truncateSummaryTxt(i, singleCharWidth);
+        }
+
+        self.hasMoreCommits = commitGraphPage['has_more_commits'];
+        self.isLoadingMoreCommits = false;
+        self.commitTableSVG.setAttribute('height', ((self.rows.length + 1) * self.Y_SPACING).toString());
+        self.setVisibleCommits();
+    }
+
+    /**
+     * Makes a row from its draw properties. Child lines that belong in rows above are added to those rows.
+     */
+    makeRow(commit, rowIndex, lines, graphWidth, singleCharWidth) {
+        const self = this,
+            elements = commit['elements'];
+        let row = {'sha': commit['sha'], 'pixel_y': commit['pixel_y'], 'lines': lines, 'branches': [], 'circle': null, 'summaryTxt': null, 'authorName': null, 'authorTime': null, 'backRect': null};
+        for (const childLine of elements['child_lines']) {
+            const line = self.makeSVG(childLine['tag'], childLine['attrs']);
+            // Remembered so the line can be taken out again if this row is replaced by a delta.
+            line.ownerSHA = commit['sha'];
+            if (childLine['row-y'] < rowIndex) {
+                self.rows[childLine['row-y']]['lines'].push(line);
+            } else if (childLine['row-y'] === rowIndex) {
+                row['lines'].push(line);
+            } else {
+                console.error("ERROR: A child line is trying to be added after the current node!");
+            }
+        }
+        row['circle'] = self.makeSVG(elements['circle']['tag'], elements['circle']['attrs']);
+
+        const summaryTxt = self.makeSVG(elements['summary_text']['tag'], elements['summary_text']['attrs']);
+        summaryTxt.textContent = elements['summary_text']['textContent'];
+        row['summaryTxt'] = summaryTxt;
+
+        const authorTimeX = graphWidth - (elements['author_time']['textContent'].length * singleCharWidth) - self.RIGHT_TEXT_SPACING;
+        elements['author_time']['attrs']['x'] = authorTimeX;
+        const authorTime = self.makeSVG(elements['author_time']['tag'], elements['author_time']['attrs']);
+        authorTime.textContent = elements['author_time']['textContent'];
+        row['authorTime'] = authorTime;
+
+        elements['author_name']['attrs']['x'] = authorTimeX - (elements['author_name']['textContent'].length * singleCharWidth) - self.RIGHT_TEXT_SPACING;
+        const authorName = self.makeSVG(elements['author_name']['tag'], elements['author_name']['attrs']);
+        authorName.textContent = elements['author_name']['textContent'];
+        row['authorName'] = authorName;
+
+        elements['back_rect']['attrs']['width'] = graphWidth - elements['circle']['attrs']['cx'];
+        const backRect = self.makeSVG(elements['back_rect']['tag'], elements['back_rect']['attrs']);
+        backRect.onclick = self.getClickFunction(commit['sha']);
+        backRect.ondblclick = self.getDblClickFunction(commit['sha']);
+        backRect.oncontextmenu = self.getContextFunction(commit['sha']);
+        row['backRect'] = backRect;
+
+        return row;
+    }
+
+    /**
+     * Applies a delta from the back-end: rows are removed from the top, the remaining rows move down to make room for the
+     * added rows, and the new rows and rows whose layout changed are replaced.
+     */
+    applyRowDelta(rowDelta, graphWidth, singleCharWidth) {
+        const self = this,
+            keptRows = self.rows.slice(rowDelta['removed_row_count']),
+            shiftY = (rowDelta['added_row_count'] - rowDelta['removed_row_count']) * self.Y_SPACING;
+
+        if (shiftY !== 0) {
+            for (const row of keptRows) {
+                self.shiftRow(row, shiftY);
+            }
+        }
+        self.rows = new Array(rowDelta['added_row_count']).fill(null).concat(keptRows);
+
+        // Rows come from top to bottom, so any row a child line gets added to is already in place.
+        for (const commit of rowDelta['changed_svg_row_draw_properties']) {
+            const rowIndex = Math.round((commit['pixel_y'] - self.Y_OFFSET) / self.Y_SPACING);
+            let lines = [];
+            if (self.rows[rowIndex] !== null) {
+                for (let i = 0; i <= rowIndex; i++) {
+                    self.rows[i]['lines'] = self.rows[i]['lines'].filter((line) => line.ownerSHA !== commit['sha']);
+                }
+                lines = self.rows[rowIndex]['lines'];
+            }
+            self.rows[rowIndex] = self.makeRow(commit, rowIndex, lines, graphWidth, singleCharWidth);
+        }
+    }
+
+    shiftRow(row, shiftY) {
+        row['pixel_y'] += shiftY;
+        const elements = row['lines'].concat([row['circle'], row['summaryTxt'], row['authorName'], row['authorTime'], row['backRect']]);
+        for (const element of elements) {
+            element.shiftY = (element.shiftY || 0) + shiftY;
+            element.setAttribute('transform', 'translate(0 ' + element.shiftY + ')');
+        }
+    }
+
     selectRowOnRefresh(headSHA) {
         const self = this;
 
@@ -158,7 +240,7 @@ export class SVGManager {
             });
             if (rowIndex !== -1) {
                 const summaryTxtElem = self.rows[rowIndex]['summaryTxt'];
-                const pixel_y = Number(self.rows[rowIndex]['circle'].getAttribute('cy'));
+                const pixel_y = self.rows[rowIndex]['pixel_y'];
                 let currentPixelX = Number(summaryTxtElem.getAttribute('x'));
                 for (let j = 0; j < branchDrawProperties[i][1].length; j++) {
                     const branch = branchDrawProperties[i][1][j];
@@ -243,6 +325,12 @@ export class SVGManager {
             self.commitsBottom = Math.min(Math.round((renderingAreaBottom - self.Y_OFFSET) / self.Y_SPACING), self.rows.length - 1);
 
             self.renderVisibleCommits();
+
+            // Load the next page of the graph once the last loaded row is about to be shown.
+            if (self.hasMoreCommits && !self.isLoadingMoreCommits && self.commitsBottom >= self.rows.length - 1) {
+                self.isLoadingMoreCommits = true;
+                emit("load-more-commits").then();
+            }
         }
     }
 